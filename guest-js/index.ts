@@ -1,44 +1,115 @@
-import { convertFileSrc } from "@tauri-apps/api/core";
+import { Channel, convertFileSrc } from "@tauri-apps/api/core";
 
 // Taken from Tauri's IPC serialization logic
+function serializeJson(value: unknown): string {
+  return JSON.stringify(value, (_k, val) => {
+    const SERIALIZE_TO_IPC_FN = "__TAURI_TO_IPC_KEY__";
+
+    if (val instanceof Map) {
+      return Object.fromEntries(val.entries());
+    } else if (val instanceof Uint8Array) {
+      return Array.from(val);
+    } else if (val instanceof ArrayBuffer) {
+      return Array.from(new Uint8Array(val));
+    } else if (
+      typeof val === "object" &&
+      val !== null &&
+      SERIALIZE_TO_IPC_FN in val
+    ) {
+      return val[SERIALIZE_TO_IPC_FN]();
+    } else {
+      return val;
+    }
+  });
+}
+
 function processIpcArgs(...args: unknown[]) {
   if (
-    (args.length === 1 && args instanceof ArrayBuffer) ||
-    ArrayBuffer.isView(args) ||
-    Array.isArray(args)
+    args.length === 1 &&
+    (args[0] instanceof ArrayBuffer || ArrayBuffer.isView(args[0]))
   ) {
     return {
       contentType: "application/octet-stream",
       data: args[0] as ArrayBuffer,
     };
   } else {
-    const data = JSON.stringify(args, (_k, val) => {
-      const SERIALIZE_TO_IPC_FN = "__TAURI_TO_IPC_KEY__";
-
-      if (val instanceof Map) {
-        return Object.fromEntries(val.entries());
-      } else if (val instanceof Uint8Array) {
-        return Array.from(val);
-      } else if (val instanceof ArrayBuffer) {
-        return Array.from(new Uint8Array(val));
-      } else if (
-        typeof val === "object" &&
-        val !== null &&
-        SERIALIZE_TO_IPC_FN in val
-      ) {
-        return val[SERIALIZE_TO_IPC_FN]();
-      } else {
-        return val;
-      }
-    });
-
     return {
       contentType: "application/json",
-      data,
+      data: serializeJson(args),
     };
   }
 }
 
+/**
+ * An error returned by a command as an `AppError`, or by the router itself,
+ * serialized as a `{ type, message, details? }` envelope.
+ *
+ * @example
+ * ```ts
+ * import { invoke, RouterError } from "tauri-plugin-router";
+ *
+ * try {
+ *   await invoke("open", path);
+ * } catch (e) {
+ *   if (e instanceof RouterError && e.code === "FILE_NOT_READABLE") {
+ *     console.log(e.details);
+ *   }
+ * }
+ * ```
+ */
+export class RouterError extends Error {
+  /** The machine-readable error code, such as `CommandNotFound`. */
+  readonly code: string;
+  /** The structured details of the error, if any. */
+  readonly details?: unknown;
+  /** The status code of the response. */
+  readonly status: number;
+
+  constructor(code: string, message: string, status: number, details?: unknown) {
+    super(message);
+    this.name = "RouterError";
+    this.code = code;
+    this.status = status;
+    this.details = details;
+  }
+}
+
+function isErrorEnvelope(
+  body: unknown,
+): body is { type: string; message: string; details?: unknown } {
+  return (
+    typeof body === "object" &&
+    body !== null &&
+    typeof (body as Record<string, unknown>).type === "string" &&
+    typeof (body as Record<string, unknown>).message === "string"
+  );
+}
+
+/**
+ * Options of a command invocation.
+ */
+export interface InvokeOptions<P = unknown> {
+  /**
+   * Aborts the command: the promise rejects with the signal's reason,
+   * and the command's future is dropped on the Rust side.
+   */
+  signal?: AbortSignal;
+  /**
+   * The HTTP method of the request, `POST` by default.
+   * Arguments are not sent with `GET` and `HEAD` requests, and `HEAD` requests resolve to `undefined`.
+   */
+  method?: "GET" | "HEAD" | "POST" | "PUT" | "PATCH" | "DELETE";
+  /**
+   * Receives the chunks of a `StreamBody` response as they are produced,
+   * the promise then resolves with an empty body once the stream ends and every chunk has been received.
+   */
+  onChunk?: (chunk: ArrayBuffer) => void;
+  /**
+   * Receives the events sent by the command through its `Progress<T>` extractor.
+   */
+  onProgress?: (event: P) => void;
+}
+
 /**
  * Invokes a command on the Tauri router plugin.
  *
@@ -52,28 +123,253 @@ function processIpcArgs(...args: unknown[]) {
  *
  * @param cmd The command to invoke.
  * @param args The arguments to pass to the command.
- * @returns A promise that resolves to the result of the command.
+ * @returns A promise that resolves to the result of the command,
+ * or rejects with the error returned by the command, as a {@link RouterError} for `AppError`s and router errors.
  */
 export async function invoke<T>(
   cmd: string,
   ...args: unknown[]
 ): Promise<T | ArrayBuffer | string> {
-  const url = convertFileSrc(cmd, "router");
+  const { contentType, data } = processIpcArgs(...args);
+  return request<T>(cmd, contentType, data);
+}
 
+/**
+ * Invokes a command on the Tauri router plugin, like {@link invoke}, with options.
+ *
+ * @example
+ * ```ts
+ * import { invokeWithOptions } from "tauri-plugin-router";
+ *
+ * const controller = new AbortController();
+ * const results = invokeWithOptions("search", ["tauri"], { signal: controller.signal });
+ * controller.abort();
+ * ```
+ *
+ * @param cmd The command to invoke.
+ * @param args The arguments to pass to the command.
+ * @param options The options of the invocation.
+ * @returns A promise that resolves to the result of the command,
+ * or rejects with the error returned by the command, as a {@link RouterError} for `AppError`s and router errors.
+ */
+export async function invokeWithOptions<T, P = unknown>(
+  cmd: string,
+  args: unknown[],
+  options: InvokeOptions<P>,
+): Promise<T | ArrayBuffer | string> {
   const { contentType, data } = processIpcArgs(...args);
+  return request<T, P>(cmd, contentType, data, options);
+}
 
-  const response = await fetch(url, {
-    method: "POST",
-    headers: {
-      "Content-Type": contentType,
-    },
-    body: data,
-  });
+/**
+ * Invokes a command registered with `Router::command_with_arg_names`,
+ * passing its arguments by name.
+ *
+ * @example
+ * ```ts
+ * import { invokeNamed } from "tauri-plugin-router";
+ *
+ * const result = await invokeNamed("greet", { name: "Tauri", greeting: "Hello" });
+ * console.log(result);
+ * ```
+ *
+ * @param cmd The command to invoke.
+ * @param args The arguments to pass to the command, keyed by name.
+ * @param options The options of the invocation.
+ * @returns A promise that resolves to the result of the command,
+ * or rejects with the error returned by the command, as a {@link RouterError} for `AppError`s and router errors.
+ */
+export async function invokeNamed<T, P = unknown>(
+  cmd: string,
+  args: Record<string, unknown>,
+  options?: InvokeOptions<P>,
+): Promise<T | ArrayBuffer | string> {
+  return request<T, P>(cmd, "application/json", serializeJson(args), options);
+}
+
+/**
+ * Invokes a command with a JSON body, to be extracted as a whole using `Json<T>`.
+ *
+ * @example
+ * ```ts
+ * import { invokeJson } from "tauri-plugin-router";
+ *
+ * await invokeJson("save", { title: "Notes", content: "..." });
+ * ```
+ *
+ * @param cmd The command to invoke.
+ * @param body The value to send as the JSON body.
+ * @param options The options of the invocation.
+ * @returns A promise that resolves to the result of the command,
+ * or rejects with the error returned by the command, as a {@link RouterError} for `AppError`s and router errors.
+ */
+export async function invokeJson<T, P = unknown>(
+  cmd: string,
+  body: unknown,
+  options?: InvokeOptions<P>,
+): Promise<T | ArrayBuffer | string> {
+  return request<T, P>(cmd, "application/json", serializeJson(body), options);
+}
+
+/**
+ * Returns the URL of a command, to be loaded directly by `<img>`, `<video>` or `<a download>` elements
+ * with a `GET` request.
+ *
+ * Elements without the `crossorigin` attribute don't send an `Origin` header, so their requests are
+ * rejected when the allowed origins are restricted, unless the route is allowed with `CorsConfig::allow_missing_origin`.
+ *
+ * @example
+ * ```ts
+ * import { commandUrl } from "tauri-plugin-router";
+ *
+ * img.src = commandUrl("thumbnails/42", { size: "small" });
+ * ```
+ *
+ * @param cmd The command to load.
+ * @param query Optional query string parameters, extracted using `Query<T>`.
+ * @returns The URL of the command on the `router` protocol.
+ */
+export function commandUrl(
+  cmd: string,
+  query?: Record<string, string>,
+): string {
+  const search = query ? new URLSearchParams(query).toString() : "";
+  return routerUrl(search ? `${cmd}?${search}` : cmd);
+}
+
+/**
+ * Returns the URL of a command on the `router` protocol, encoding the characters of its path
+ * that aren't valid in a URL but keeping existing escapes, so that a path parameter encoded
+ * with `encodeURIComponent` can contain a `/` or a `?`.
+ */
+function routerUrl(cmd: string): string {
+  const index = cmd.indexOf("?");
+  const path = index === -1 ? cmd : cmd.slice(0, index);
+  const search = index === -1 ? "" : cmd.slice(index);
+  const encoded = path.replace(
+    /%(?![0-9A-Fa-f]{2})|[^\w\-.~!$&'()*+,;=:@%/]/gu,
+    (char) => encodeURIComponent(char),
+  );
+  return `${convertFileSrc("", "router")}${encoded}${search}`;
+}
+
+async function request<T, P>(
+  cmd: string,
+  contentType: string,
+  data: BodyInit,
+  options?: InvokeOptions<P>,
+): Promise<T | ArrayBuffer | string> {
+  const url = routerUrl(cmd);
+  const headers: Record<string, string> = {
+    "Content-Type": contentType,
+  };
+
+  const signal = options?.signal;
+  signal?.throwIfAborted();
+
+  // chunks of streamed responses are sent through a channel instead of the response body,
+  // large chunks are fetched separately and may arrive after the response
+  const onChunk = options?.onChunk;
+  let receivedChunks = 0;
+  let onAllChunks: (() => void) | undefined;
+  if (onChunk) {
+    const channel = new Channel<ArrayBuffer>();
+    channel.onmessage = (chunk) => {
+      onChunk(chunk);
+      receivedChunks += 1;
+      onAllChunks?.();
+    };
+    headers["X-Router-Channel"] = channel.toJSON();
+  }
+
+  const onProgress = options?.onProgress;
+  if (onProgress) {
+    const channel = new Channel<P>();
+    channel.onmessage = onProgress;
+    headers["X-Router-Progress-Channel"] = channel.toJSON();
+  }
+
+  // tell the router to drop the command's future when the signal is aborted
+  let onAbort: (() => void) | undefined;
+  if (signal) {
+    const requestId = crypto.randomUUID();
+    headers["X-Router-Request-Id"] = requestId;
+    onAbort = () => {
+      fetch(routerUrl("__router__/cancel"), {
+        method: "POST",
+        headers: { "Content-Type": "text/plain" },
+        body: requestId,
+      }).catch(() => {});
+    };
+    signal.addEventListener("abort", onAbort, { once: true });
+  }
+
+  try {
+    const method = options?.method ?? "POST";
+    const response = await fetch(url, {
+      method,
+      headers,
+      body: method === "GET" || method === "HEAD" ? undefined : data,
+      signal,
+    });
+
+    const body = await readBody<T>(response, method);
+
+    const chunks = Number(response.headers.get("X-Router-Chunks") ?? 0);
+    if (receivedChunks < chunks) {
+      await new Promise<void>((resolve, reject) => {
+        // stop waiting for the remaining chunks when the signal is aborted
+        signal?.throwIfAborted();
+        const onWaitAbort = () => reject(signal?.reason);
+        signal?.addEventListener("abort", onWaitAbort, { once: true });
+        onAllChunks = () => {
+          if (receivedChunks >= chunks) {
+            signal?.removeEventListener("abort", onWaitAbort);
+            resolve();
+          }
+        };
+      });
+    }
+
+    if (!response.ok) {
+      if (isErrorEnvelope(body)) {
+        throw new RouterError(
+          body.type,
+          body.message,
+          response.status,
+          body.details,
+        );
+      }
+      throw body;
+    }
+
+    return body;
+  } finally {
+    if (onAbort) {
+      signal?.removeEventListener("abort", onAbort);
+    }
+  }
+}
+
+async function readBody<T>(
+  response: Response,
+  method: string,
+): Promise<T | ArrayBuffer | string> {
+  // `HEAD` requests and empty responses have no body to parse
+  if (
+    method === "HEAD" ||
+    response.status === 204 ||
+    response.headers.get("content-length") === "0"
+  ) {
+    return undefined as T;
+  }
 
   // we need to split here because on Android the content-type gets duplicated
   switch ((response.headers.get("content-type") || "").split(",")[0]) {
-    case "application/json":
-      return response.json();
+    case "application/json": {
+      const text = await response.text();
+      return text ? JSON.parse(text) : (undefined as T);
+    }
     case "text/plain":
       return response.text();
     default:
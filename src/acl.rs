@@ -0,0 +1,32 @@
+use tauri::{Manager, Runtime};
+
+/// Name of the command checked against the Tauri capabilities for the fallback handler,
+/// normalized routes never start with a `/` so it can't collide with a route.
+pub(crate) const FALLBACK_COMMAND: &str = "/fallback";
+
+/// Checks that the capabilities granted to the calling webview allow the given command.
+///
+/// Routes are checked as is, `files/:id` is checked as the `plugin:router|files/:id` command
+/// for example, so that distinct routes never share a permission. The plugin can't generate
+/// permissions for the routes of the app, which are spelled in the app's own permission files.
+pub(crate) fn check_access<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    webview_label: &str,
+    command: &str,
+) -> crate::Result<()> {
+    #[cfg(feature = "unstable")]
+    let webview = app_handle.get_webview(webview_label);
+    #[cfg(not(feature = "unstable"))]
+    let webview = app_handle.get_webview_window(webview_label);
+
+    let webview =
+        webview.ok_or_else(|| crate::Error::WebviewNotFound(webview_label.to_string()))?;
+
+    match webview.resolve_command_scope::<serde_json::Value>("router", command) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(crate::Error::Forbidden(format!(
+            "`plugin:router|{command}` is not allowed for webview `{webview_label}`"
+        ))),
+        Err(e) => Err(crate::Error::Forbidden(e.to_string())),
+    }
+}
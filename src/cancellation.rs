@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+
+/// Header of the id the frontend assigns to a request it may abort.
+pub(crate) const REQUEST_ID_HEADER: &str = "x-router-request-id";
+
+/// Command the frontend invokes with the id of a request to abort as its body.
+pub(crate) const CANCEL_COMMAND: &str = "__router__/cancel";
+
+/// How long the cancellation of a request that isn't registered yet is remembered.
+const EARLY_CANCELLATION_TTL: Duration = Duration::from_secs(30);
+
+/// Registry of the cancellation tokens of in-flight requests, keyed by webview label and request id.
+#[derive(Default)]
+pub(crate) struct Cancellations {
+    pub(crate) tokens: Mutex<HashMap<(String, String), CancellationToken>>,
+    /// Requests cancelled before they were registered, as the cancel request can arrive first.
+    pub(crate) early: Mutex<HashMap<(String, String), Instant>>,
+}
+
+impl Cancellations {
+    /// Registers a request, the returned guard unregisters it when dropped.
+    ///
+    /// The token is already cancelled if the request was cancelled before being registered.
+    pub(crate) fn register(
+        &self,
+        webview_label: &str,
+        request_id: &str,
+    ) -> (CancellationToken, CancellationGuard<'_>) {
+        let key = (webview_label.to_string(), request_id.to_string());
+        let token = CancellationToken::new();
+        if self.take_early(&key) {
+            token.cancel();
+        }
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(key.clone(), token.clone());
+        let guard = CancellationGuard {
+            cancellations: self,
+            key,
+        };
+        (token, guard)
+    }
+
+    /// Cancels an in-flight request of the given webview, returns whether it was found.
+    ///
+    /// Requests that are not registered yet are remembered for a short time,
+    /// so that they are cancelled as soon as they are registered.
+    pub(crate) fn cancel(&self, webview_label: &str, request_id: &str) -> bool {
+        let key = (webview_label.to_string(), request_id.to_string());
+        let token = self.tokens.lock().unwrap().remove(&key);
+        match token {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => {
+                let mut early = self.early.lock().unwrap();
+                early.retain(|_, cancelled_at| cancelled_at.elapsed() < EARLY_CANCELLATION_TTL);
+                early.insert(key, Instant::now());
+                false
+            }
+        }
+    }
+
+    /// Returns whether the request was cancelled before being registered, and forgets it.
+    fn take_early(&self, key: &(String, String)) -> bool {
+        let mut early = self.early.lock().unwrap();
+        if early.is_empty() {
+            return false;
+        }
+        early.retain(|_, cancelled_at| cancelled_at.elapsed() < EARLY_CANCELLATION_TTL);
+        early.remove(key).is_some()
+    }
+}
+
+/// Unregisters a request from [Cancellations] when dropped.
+pub(crate) struct CancellationGuard<'a> {
+    cancellations: &'a Cancellations,
+    key: (String, String),
+}
+
+impl Drop for CancellationGuard<'_> {
+    fn drop(&mut self) {
+        self.cancellations.tokens.lock().unwrap().remove(&self.key);
+    }
+}
@@ -1,12 +1,68 @@
+use std::sync::Arc;
+
 use tauri::{Manager, Runtime};
 
+use crate::StateMap;
+
+/// The JSON arguments of a request body.
+#[derive(Clone)]
+pub(crate) enum JsonArgs {
+    /// Arguments passed as a JSON array, taken in order.
+    Positional(std::vec::IntoIter<serde_json::Value>),
+    /// Arguments passed as a JSON object, taken by name.
+    Named(serde_json::Map<String, serde_json::Value>),
+}
+
 /// Context for a command execution.
 ///
 /// This struct provides access to the application handle and webview label.
 pub struct CommandContext<R: Runtime> {
     pub(crate) app_handle: tauri::AppHandle<R>,
     pub(crate) webview_label: String,
-    pub(crate) json_args: Option<std::vec::IntoIter<serde_json::Value>>,
+    pub(crate) json_args: Option<JsonArgs>,
+    pub(crate) arg_names: Option<Arc<[String]>>,
+    pub(crate) arg_index: usize,
+    pub(crate) lenient_args: bool,
+    pub(crate) state: Arc<StateMap>,
+    pub(crate) path_params: Vec<(String, String)>,
+    pub(crate) query: Option<String>,
+    pub(crate) deadline: Option<std::time::Instant>,
+    pub(crate) cancellation_token: tokio_util::sync::CancellationToken,
+    pub(crate) command: String,
+    pub(crate) rejection_handler: Option<crate::rejection::RejectionHandler>,
+    pub(crate) registered_commands: Arc<[String]>,
+}
+
+impl<R: Runtime> Clone for CommandContext<R> {
+    fn clone(&self) -> Self {
+        Self {
+            app_handle: self.app_handle.clone(),
+            webview_label: self.webview_label.clone(),
+            json_args: self.json_args.clone(),
+            arg_names: self.arg_names.clone(),
+            arg_index: self.arg_index,
+            lenient_args: self.lenient_args,
+            state: Arc::clone(&self.state),
+            path_params: self.path_params.clone(),
+            query: self.query.clone(),
+            deadline: self.deadline,
+            cancellation_token: self.cancellation_token.clone(),
+            command: self.command.clone(),
+            rejection_handler: self.rejection_handler.clone(),
+            registered_commands: Arc::clone(&self.registered_commands),
+        }
+    }
+}
+
+/// Extracts the [CommandContext] of the request.
+impl<R: Runtime> crate::FromRequestParts<R> for CommandContext<R> {
+    async fn from_request_parts(
+        _parts: &mut tauri::http::request::Parts,
+        _body: &[u8],
+        ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        Ok(ctx.clone())
+    }
 }
 
 impl<R: Runtime> CommandContext<R> {
@@ -20,6 +76,27 @@ impl<R: Runtime> CommandContext<R> {
         &self.webview_label
     }
 
+    /// Returns the instant at which the command times out, if it has a timeout.
+    pub fn deadline(&self) -> Option<std::time::Instant> {
+        self.deadline
+    }
+
+    /// Returns the token that is cancelled when the frontend aborts the request.
+    pub fn cancellation_token(&self) -> &tokio_util::sync::CancellationToken {
+        &self.cancellation_token
+    }
+
+    /// Returns the command being executed, as registered, such as `documents/:id`,
+    /// or the requested command for the [fallback](crate::Router::fallback) handler.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Returns the names of the commands registered on the router, sorted.
+    pub fn registered_commands(&self) -> &[String] {
+        &self.registered_commands
+    }
+
     /// Returns the webview window that made the request.
     pub fn webview_window(&self) -> crate::Result<tauri::WebviewWindow<R>> {
         self.app_handle
@@ -41,22 +118,188 @@ impl<R: Runtime> CommandContext<R> {
         self.webview().map(|w| w.window())
     }
 
-    /// Takes the next JSON argument from the request body.
-    pub(crate) fn take_json_arg(&mut self, body: &[u8]) -> crate::Result<serde_json::Value> {
+    /// Returns a clone of the state of type `T`.
+    ///
+    /// State attached to the [Router](crate::Router) via [Router::with_state](crate::Router::with_state)
+    /// takes precedence over state managed by Tauri via [Manager::manage].
+    pub fn state<T: Clone + Send + Sync + 'static>(&self) -> crate::Result<T> {
+        if let Some(state) = self
+            .state
+            .get(&std::any::TypeId::of::<T>())
+            .and_then(|s| s.downcast_ref::<T>())
+        {
+            return Ok(state.clone());
+        }
+
+        self.app_handle
+            .try_state::<T>()
+            .map(|s| s.inner().clone())
+            .ok_or_else(|| crate::Error::StateNotFound(std::any::type_name::<T>().to_string()))
+    }
+
+    /// Takes the next JSON argument from the request body and deserializes it.
+    ///
+    /// A missing argument is deserialized from `null`, so that `Option<T>` arguments resolve to `None`.
+    pub(crate) fn deserialize_json_arg<T: serde::de::DeserializeOwned>(
+        &mut self,
+        body: &[u8],
+    ) -> crate::Result<T> {
+        match self.next_json_arg(body)? {
+            Some(arg) => self.deserialize_json_value(arg),
+            None => T::deserialize(serde_json::Value::Null).map_err(|_| self.missing_json_arg()),
+        }
+    }
+
+    /// Deserializes a JSON argument taken by [CommandContext::next_json_arg].
+    pub(crate) fn deserialize_json_value<T: serde::de::DeserializeOwned>(
+        &self,
+        arg: serde_json::Value,
+    ) -> crate::Result<T> {
+        serde_json::from_value(arg).map_err(|e| match self.current_arg_name() {
+            Some(name) => crate::Error::DeserializationError(format!(
+                "JSON deserialization error for argument `{}`: {}",
+                name, e
+            )),
+            None => {
+                crate::Error::DeserializationError(format!("JSON deserialization error: {}", e))
+            }
+        })
+    }
+
+    /// Returns the error for a missing JSON argument taken by [CommandContext::next_json_arg].
+    fn missing_json_arg(&self) -> crate::Error {
+        match self.current_arg_name() {
+            Some(name) => crate::Error::InvalidArgs(format!("missing argument `{name}`")),
+            None => crate::Error::InvalidArgs("no more arguments available".to_string()),
+        }
+    }
+
+    /// Returns the name of the last taken JSON argument, if the command uses named arguments.
+    fn current_arg_name(&self) -> Option<&str> {
+        match self.json_args {
+            Some(JsonArgs::Named(_)) => self
+                .arg_names
+                .as_ref()
+                .and_then(|names| names.get(self.arg_index.checked_sub(1)?))
+                .map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// Takes the next JSON argument from the request body, or `None` if it is missing.
+    ///
+    /// The body is either a JSON array of positional arguments,
+    /// or a JSON object of named arguments if the command was registered with
+    /// [Router::command_with_arg_names](crate::Router::command_with_arg_names).
+    pub(crate) fn next_json_arg(
+        &mut self,
+        body: &[u8],
+    ) -> crate::Result<Option<serde_json::Value>> {
         // If json_args is not initialized, parse the request body
         if self.json_args.is_none() {
-            let json_args: Vec<serde_json::Value> = serde_json::from_slice(body).map_err(|e| {
-                crate::Error::DeserializationError(format!("Failed to parse request body: {}", e))
-            })?;
+            self.json_args = Some(self.parse_json_args(body)?);
+        }
+
+        let index = self.arg_index;
+        self.arg_index += 1;
+
+        match self.json_args.as_mut().unwrap() {
+            JsonArgs::Positional(args) => Ok(args.next()),
+            JsonArgs::Named(args) => {
+                let name = self
+                    .arg_names
+                    .as_ref()
+                    .and_then(|names| names.get(index))
+                    .ok_or_else(|| {
+                        crate::Error::InvalidArgs(format!(
+                            "no name registered for argument {index}"
+                        ))
+                    })?;
+                Ok(args.remove(name))
+            }
+        }
+    }
 
-            self.json_args = Some(json_args.into_iter());
+    /// Parses a request body that no extractor read as positional JSON arguments,
+    /// so that [CommandContext::ensure_no_surplus_args] rejects them.
+    ///
+    /// Bodies that are not a JSON array, such as the empty body of a `GET` request, are ignored.
+    pub(crate) fn parse_unread_args(&mut self, body: &[u8]) {
+        if self.json_args.is_some() || self.lenient_args || body.is_empty() {
+            return;
+        }
+        if let Ok(serde_json::Value::Array(args)) = serde_json::from_slice(body) {
+            self.json_args = Some(JsonArgs::Positional(args.into_iter()));
         }
+    }
 
-        // Get the next argument from the iterator
-        self.json_args
-            .as_mut()
-            .unwrap()
-            .next()
-            .ok_or_else(|| crate::Error::InvalidArgs("no more arguments available".to_string()))
+    /// Checks that all JSON arguments of the request body were taken by the handler.
+    ///
+    /// Always succeeds if the router was configured with [Router::lenient_args](crate::Router::lenient_args).
+    pub(crate) fn ensure_no_surplus_args(&self) -> crate::Result<()> {
+        if self.lenient_args {
+            return Ok(());
+        }
+
+        match &self.json_args {
+            Some(JsonArgs::Positional(args)) if args.len() > 0 => {
+                Err(crate::Error::InvalidArgs(format!(
+                    "too many arguments: expected {}, received {}",
+                    self.arg_index,
+                    self.arg_index + args.len()
+                )))
+            }
+            Some(JsonArgs::Named(args)) => match args.keys().next() {
+                Some(key) => Err(crate::Error::InvalidArgs(format!(
+                    "unexpected argument `{key}`: expected {} arguments, received {}",
+                    self.arg_index,
+                    self.arg_index + args.len()
+                ))),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Converts an extractor failure into a response, using the rejection handler of the router if any.
+    pub(crate) fn reject(
+        &self,
+        error: crate::Error,
+        arg_index: Option<usize>,
+    ) -> tauri::http::Response<Vec<u8>> {
+        match &self.rejection_handler {
+            Some(handler) => handler(crate::Rejection {
+                command: self.command.clone(),
+                arg_index,
+                error,
+            }),
+            None => crate::response::error(error),
+        }
+    }
+
+    fn parse_json_args(&self, body: &[u8]) -> crate::Result<JsonArgs> {
+        let json: serde_json::Value = serde_json::from_slice(body).map_err(|e| {
+            crate::Error::DeserializationError(format!("Failed to parse request body: {}", e))
+        })?;
+
+        match (json, &self.arg_names) {
+            (serde_json::Value::Array(args), _) => Ok(JsonArgs::Positional(args.into_iter())),
+            (serde_json::Value::Object(args), Some(names)) => {
+                if let Some(key) = args.keys().find(|key| !names.contains(key)) {
+                    return Err(crate::Error::InvalidArgs(format!(
+                        "unexpected argument `{key}`"
+                    )));
+                }
+                Ok(JsonArgs::Named(args))
+            }
+            (serde_json::Value::Object(_), None) => Err(crate::Error::InvalidArgs(
+                "named arguments are not supported by this command, expected a JSON array"
+                    .to_string(),
+            )),
+            (json, _) => Err(crate::Error::DeserializationError(format!(
+                "Failed to parse request body: expected a JSON array or object, found {}",
+                json
+            ))),
+        }
     }
 }
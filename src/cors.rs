@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use tauri::http::{header::*, HeaderMap, HeaderValue, Method};
+
+/// Request headers sent by the frontend client of the plugin, always allowed.
+const PLUGIN_HEADERS: &[&str] = &[
+    crate::cancellation::REQUEST_ID_HEADER,
+    crate::stream::STREAM_CHANNEL_HEADER,
+    crate::extract::PROGRESS_CHANNEL_HEADER,
+];
+
+/// CORS and origin policy of the `router` protocol.
+///
+/// The default configuration allows any origin and any request header,
+/// and only accepts `POST` requests.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{Builder, CorsConfig, Router};
+///
+/// fn greet(name: String) -> String {
+///    format!("Hello, {}!", name)
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().command("greet", greet);
+///
+///     let cors = CorsConfig::new()
+///         .allow_origins(["tauri://localhost", "http://tauri.localhost"])
+///         .allow_headers(["content-type"])
+///         .max_age(std::time::Duration::from_secs(600));
+///
+///     let app = tauri::Builder::default()
+///         .plugin(Builder::new(router).cors(cors).build());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    origins: Option<Vec<String>>,
+    headers: Option<Vec<String>>,
+    methods: Vec<Method>,
+    originless_commands: Vec<String>,
+    max_age: Option<Duration>,
+    credentials: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CorsConfig {
+    /// Creates a new configuration that allows any origin and any request header.
+    pub fn new() -> Self {
+        Self {
+            origins: None,
+            headers: None,
+            methods: vec![Method::POST],
+            originless_commands: Vec::new(),
+            max_age: None,
+            credentials: false,
+        }
+    }
+
+    /// Allows requests from the given origin, such as `tauri://localhost`.
+    ///
+    /// Once an origin is allowed, requests from any other origin, or without an `Origin` header,
+    /// are rejected with [Error::Forbidden](crate::Error::Forbidden) before reaching the router,
+    /// see [CorsConfig::allow_missing_origin] for media elements.
+    pub fn allow_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origins
+            .get_or_insert_with(Vec::new)
+            .push(origin.into());
+        self
+    }
+
+    /// Allows requests from the given origins, see [CorsConfig::allow_origin].
+    pub fn allow_origins<I, S>(self, origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        origins.into_iter().fold(self, Self::allow_origin)
+    }
+
+    /// Allows `GET` and `HEAD` requests without an `Origin` header to the commands matching the given patterns,
+    /// where `*` matches any sequence of characters and `?` matches a single character.
+    ///
+    /// Media elements such as `<img>` and `<video>` only send an `Origin` header with the `crossorigin` attribute,
+    /// so their requests are rejected once the allowed origins are restricted, unless their command is allowed here.
+    /// Responses to these requests have no `Access-Control-Allow-Origin` header, so scripts can't read them.
+    pub fn allow_missing_origin<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.originless_commands
+            .extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Allows the given request header. Once a header is allowed, only allowed headers are advertised,
+    /// along with the headers sent by the frontend client of the plugin.
+    pub fn allow_header(mut self, header: impl Into<String>) -> Self {
+        self.headers
+            .get_or_insert_with(Vec::new)
+            .push(header.into());
+        self
+    }
+
+    /// Allows the given request headers, see [CorsConfig::allow_header].
+    pub fn allow_headers<I, S>(self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        headers.into_iter().fold(self, Self::allow_header)
+    }
+
+    /// Sets the allowed request methods, `POST` by default.
+    ///
+    /// Requests with any other method, except `OPTIONS` preflight requests,
+    /// are rejected with `405 Method Not Allowed`. Routes registered with
+    /// [Router::route](crate::Router::route) for other methods need them to be allowed here.
+    pub fn allow_methods<I: IntoIterator<Item = Method>>(mut self, methods: I) -> Self {
+        self.methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Sets how long the result of a preflight request can be cached.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets whether credentials are allowed.
+    ///
+    /// Browsers reject a wildcard origin with credentials,
+    /// so the request origin is echoed back instead when any origin is allowed.
+    pub fn allow_credentials(mut self, credentials: bool) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Returns whether the given method is allowed.
+    pub(crate) fn is_method_allowed(&self, method: &Method) -> bool {
+        self.methods.contains(method)
+    }
+
+    /// Checks the `Origin` header of a request to the given command against the allowed origins,
+    /// and returns the value of the `Access-Control-Allow-Origin` response header, if any.
+    pub(crate) fn check_origin(
+        &self,
+        method: &Method,
+        command: &str,
+        headers: &HeaderMap,
+    ) -> crate::Result<Option<HeaderValue>> {
+        let origin = headers.get(ORIGIN);
+
+        let Some(origins) = &self.origins else {
+            return match origin {
+                Some(origin) if self.credentials => Ok(Some(origin.clone())),
+                _ => Ok(Some(HeaderValue::from_static("*"))),
+            };
+        };
+
+        let Some(origin) = origin else {
+            let allowed = matches!(*method, Method::GET | Method::HEAD)
+                && self
+                    .originless_commands
+                    .iter()
+                    .any(|pattern| crate::router::matches_glob(pattern, command));
+            return if allowed {
+                Ok(None)
+            } else {
+                Err(crate::Error::Forbidden(
+                    "missing `Origin` header".to_string(),
+                ))
+            };
+        };
+        let allowed = origin
+            .to_str()
+            .is_ok_and(|origin| origins.iter().any(|allowed| allowed == origin));
+        if allowed {
+            Ok(Some(origin.clone()))
+        } else {
+            Err(crate::Error::Forbidden(format!(
+                "origin `{}` is not allowed",
+                String::from_utf8_lossy(origin.as_bytes())
+            )))
+        }
+    }
+
+    /// Adds the CORS headers to a response.
+    pub(crate) fn apply(&self, allow_origin: Option<HeaderValue>, headers: &mut HeaderMap) {
+        if let Some(allow_origin) = allow_origin {
+            headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        }
+        if self.origins.is_some() || self.credentials {
+            headers.append(VARY, HeaderValue::from_static("origin"));
+        }
+        if self.credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+
+        let allow_headers = match &self.headers {
+            Some(allowed) => {
+                let allowed = allowed
+                    .iter()
+                    .map(String::as_str)
+                    .chain(PLUGIN_HEADERS.iter().copied())
+                    .collect::<Vec<_>>();
+                HeaderValue::from_str(&allowed.join(", ")).ok()
+            }
+            None => Some(HeaderValue::from_static("*")),
+        };
+        if let Some(allow_headers) = allow_headers {
+            headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allow_headers);
+        }
+
+        // lets the frontend wait for all the chunks of a streamed response
+        headers.insert(
+            ACCESS_CONTROL_EXPOSE_HEADERS,
+            HeaderValue::from_static(crate::stream::STREAM_CHUNKS_HEADER),
+        );
+    }
+
+    /// Adds the preflight headers to a response to an `OPTIONS` request.
+    pub(crate) fn apply_preflight(&self, headers: &mut HeaderMap) {
+        let methods = self
+            .methods
+            .iter()
+            .map(Method::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Ok(methods) = HeaderValue::from_str(&methods) {
+            headers.insert(ACCESS_CONTROL_ALLOW_METHODS, methods);
+        }
+        if let Some(max_age) = self.max_age {
+            headers.insert(ACCESS_CONTROL_MAX_AGE, max_age.as_secs().into());
+        }
+    }
+}
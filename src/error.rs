@@ -3,8 +3,9 @@ use tauri::http::StatusCode;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Debug, thiserror::Error, Serialize)]
-#[serde(tag = "type", content = "message")]
+// Serialized as an `AppError`, so that the response serializer uses the status code of the error.
+#[derive(Debug, Clone, thiserror::Error, Serialize)]
+#[serde(into = "AppError")]
 pub enum Error {
     #[error("command not found: {0}")]
     CommandNotFound(String),
@@ -20,9 +21,68 @@ pub enum Error {
 
     #[error("webview not found: {0}")]
     WebviewNotFound(String),
+
+    #[error("state not found: {0}")]
+    StateNotFound(String),
+
+    #[error("middleware error: {0}")]
+    MiddlewareError(String),
+
+    #[error("unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("timeout: {0}")]
+    Timeout(String),
+
+    #[error("cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("command panicked: {0}")]
+    Panic(String),
+
+    #[error("method not allowed: {0}")]
+    MethodNotAllowed(String),
+
+    #[error("file not found: {0}")]
+    FileNotFound(String),
+
+    #[error("io error: {0}")]
+    Io(String),
+
+    #[error("range not satisfiable: {0}")]
+    RangeNotSatisfiable(String),
 }
 
 impl Error {
+    /// Returns the name of the variant, used as the error code, and the message of the error.
+    fn parts(&self) -> (&'static str, &str) {
+        match self {
+            Error::CommandNotFound(message) => ("CommandNotFound", message),
+            Error::InvalidArgs(message) => ("InvalidArgs", message),
+            Error::DeserializationError(message) => ("DeserializationError", message),
+            Error::SerializationError(message) => ("SerializationError", message),
+            Error::WebviewNotFound(message) => ("WebviewNotFound", message),
+            Error::StateNotFound(message) => ("StateNotFound", message),
+            Error::MiddlewareError(message) => ("MiddlewareError", message),
+            Error::UnsupportedMediaType(message) => ("UnsupportedMediaType", message),
+            Error::Forbidden(message) => ("Forbidden", message),
+            Error::PayloadTooLarge(message) => ("PayloadTooLarge", message),
+            Error::Timeout(message) => ("Timeout", message),
+            Error::Cancelled(message) => ("Cancelled", message),
+            Error::Panic(message) => ("Panic", message),
+            Error::MethodNotAllowed(message) => ("MethodNotAllowed", message),
+            Error::FileNotFound(message) => ("FileNotFound", message),
+            Error::Io(message) => ("Io", message),
+            Error::RangeNotSatisfiable(message) => ("RangeNotSatisfiable", message),
+        }
+    }
+
     /// Returns the appropriate HTTP status code for the error.
     pub fn status_code(&self) -> StatusCode {
         match self {
@@ -31,6 +91,140 @@ impl Error {
             Error::DeserializationError(_) => StatusCode::BAD_REQUEST,
             Error::SerializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::WebviewNotFound(_) => StatusCode::NOT_FOUND,
+            Error::StateNotFound(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::MiddlewareError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Error::Forbidden(_) => StatusCode::FORBIDDEN,
+            Error::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            // 499 Client Closed Request, nobody reads this response as the frontend aborted it
+            Error::Cancelled(_) => StatusCode::from_u16(499).unwrap(),
+            Error::Panic(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::MethodNotAllowed(_) => StatusCode::METHOD_NOT_ALLOWED,
+            Error::FileNotFound(_) => StatusCode::NOT_FOUND,
+            Error::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::RangeNotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
+        }
+    }
+}
+
+/// An application error with a machine-readable code, returned from commands
+/// directly or as the `Err` value of a [Result](std::result::Result).
+///
+/// It is serialized into the same `{ type, message }` envelope as [Error], with `type` set to the code
+/// and an optional `details` value, and is surfaced as a `RouterError` by the JavaScript client.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri::http::StatusCode;
+/// use tauri_plugin_router::{AppError, Router};
+///
+/// fn open(path: String) -> Result<String, AppError> {
+///     std::fs::read_to_string(&path).map_err(|e| {
+///         AppError::new("FILE_NOT_READABLE", e.to_string())
+///             .with_status(StatusCode::NOT_FOUND)
+///             .with_details(serde_json::json!({ "path": path }))
+///     })
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().command("open", open);
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{code}: {message}")]
+pub struct AppError {
+    code: String,
+    message: String,
+    details: Option<serde_json::Value>,
+    status: StatusCode,
+}
+
+impl AppError {
+    /// Creates a new error with the given code and message,
+    /// and a [500](StatusCode::INTERNAL_SERVER_ERROR) status code.
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            details: None,
+            status: StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    /// Sets the structured details of the error.
+    ///
+    /// Details that fail to serialize are replaced by their serialization error message.
+    pub fn with_details(mut self, details: impl Serialize) -> Self {
+        self.details = Some(
+            serde_json::to_value(details)
+                .unwrap_or_else(|e| serde_json::Value::String(e.to_string())),
+        );
+        self
+    }
+
+    /// Sets the status code of the error response.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Returns the code of the error.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Returns the message of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the structured details of the error.
+    pub fn details(&self) -> Option<&serde_json::Value> {
+        self.details.as_ref()
+    }
+
+    /// Returns the status code of the error response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+impl From<Error> for AppError {
+    fn from(error: Error) -> Self {
+        let (code, message) = error.parts();
+        Self::new(code, message).with_status(error.status_code())
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Envelope<'a> {
+            #[serde(rename = "type")]
+            code: &'a str,
+            message: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            details: Option<&'a serde_json::Value>,
+        }
+
+        // The newtype struct is transparent in JSON, its name lets the response
+        // serializer recognize the error and read its status code.
+        crate::response::with_app_error_status(self.status, || {
+            serializer.serialize_newtype_struct(
+                crate::response::APP_ERROR_NAME,
+                &Envelope {
+                    code: &self.code,
+                    message: &self.message,
+                    details: self.details.as_ref(),
+                },
+            )
+        })
+    }
 }
@@ -18,6 +18,7 @@ impl IntoResponse for Bytes {
     fn into_response(self) -> tauri::http::Response<Vec<u8>> {
         tauri::http::Response::builder()
             .header("Content-Type", "application/octet-stream")
+            .header("Content-Length", self.0.len())
             .body(self.0)
             .unwrap()
     }
@@ -0,0 +1,60 @@
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequestParts};
+
+/// An Extractor for a [tokio_util::sync::CancellationToken] that is cancelled
+/// when the frontend aborts the request with an `AbortSignal`, or when the command times out.
+///
+/// The handler future is dropped when the request is aborted or times out,
+/// the token allows work spawned by the handler to stop as well.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{CancellationToken, Router};
+///
+/// async fn search(query: String, token: CancellationToken) -> Vec<String> {
+///     let handle = tokio::spawn(async move {
+///         let mut results = Vec::new();
+///         while !token.is_cancelled() {
+///             // search the next batch of files
+///             results.push(query.clone());
+///             break;
+///         }
+///         results
+///     });
+///     handle.await.unwrap_or_default()
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().command("search", search);
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CancellationToken(pub tokio_util::sync::CancellationToken);
+
+impl<R: Runtime> FromRequestParts<R> for CancellationToken {
+    async fn from_request_parts(
+        _parts: &mut tauri::http::request::Parts,
+        _body: &[u8],
+        ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        Ok(Self(ctx.cancellation_token().clone()))
+    }
+}
+
+impl std::ops::Deref for CancellationToken {
+    type Target = tokio_util::sync::CancellationToken;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for CancellationToken {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
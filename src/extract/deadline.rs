@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequestParts};
+
+/// An Extractor for the deadline of the command, if it has a timeout.
+///
+/// The handler future is dropped when the deadline is reached,
+/// long-running work can use [Deadline::remaining] to stop and clean up before that.
+///
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use tauri_plugin_router::{Deadline, Router};
+///
+/// async fn index(deadline: Deadline) -> usize {
+///     let mut indexed = 0;
+///     while deadline.remaining().map_or(true, |remaining| remaining > Duration::from_millis(100)) {
+///         // index the next batch of files
+///         indexed += 1;
+///     }
+///     indexed
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new()
+///         .command("index", index)
+///         .command_timeout("index", Duration::from_secs(5));
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline(pub Option<Instant>);
+
+impl Deadline {
+    /// Returns the time remaining before the deadline, or `None` if the command has no timeout.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.0
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+impl<R: Runtime> FromRequestParts<R> for Deadline {
+    async fn from_request_parts(
+        _parts: &mut tauri::http::request::Parts,
+        _body: &[u8],
+        ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        Ok(Self(ctx.deadline()))
+    }
+}
@@ -0,0 +1,104 @@
+use serde::de::DeserializeOwned;
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequest, IntoResponse};
+
+/// A JSON Extractor / Response.
+///
+/// As an extractor, deserializes the whole request body, unlike plain [DeserializeOwned] arguments
+/// which each take one element of a JSON array. The request must have a `Content-Type` of
+/// `application/json` or `application/*+json`.
+///
+/// As a response, serializes the value as-is with an `application/json` content type.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{Json, Router};
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Document {
+///     title: String,
+///     content: String,
+/// }
+///
+/// fn save(Json(document): Json<Document>) -> Json<Document> {
+///    Json(document)
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().command("save", save);
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+pub struct Json<T>(pub T);
+
+impl<R: Runtime, T: DeserializeOwned + Send> FromRequest<R> for Json<T> {
+    async fn from_request(
+        req: tauri::http::Request<Vec<u8>>,
+        _ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        check_content_type(req.headers())?;
+
+        serde_json::from_slice(req.body()).map(Self).map_err(|e| {
+            crate::Error::DeserializationError(format!("JSON deserialization error: {}", e))
+        })
+    }
+}
+
+/// Checks that the request `Content-Type` is JSON, ignoring its parameters.
+fn check_content_type(headers: &tauri::http::HeaderMap) -> crate::Result<()> {
+    let content_type = headers
+        .get(tauri::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    let is_json =
+        mime == "application/json" || (mime.starts_with("application/") && mime.ends_with("+json"));
+
+    if is_json {
+        Ok(())
+    } else {
+        Err(crate::Error::UnsupportedMediaType(format!(
+            "expected `application/json`, found `{}`",
+            content_type
+        )))
+    }
+}
+
+impl<T: serde::Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> tauri::http::Response<Vec<u8>> {
+        match serde_json::to_vec(&self.0) {
+            Ok(body) => tauri::http::Response::builder()
+                .header("Content-Type", "application/json")
+                .body(body)
+                .unwrap(),
+            Err(e) => {
+                let error = format!("Failed to serialize response: {}", e);
+                crate::response::error(crate::Error::SerializationError(error))
+            }
+        }
+    }
+}
+
+impl<T> std::ops::Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
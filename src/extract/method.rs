@@ -0,0 +1,31 @@
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequestParts};
+
+/// An Extractor for the HTTP method of the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Method(pub tauri::http::Method);
+
+impl<R: Runtime> FromRequestParts<R> for Method {
+    async fn from_request_parts(
+        parts: &mut tauri::http::request::Parts,
+        _body: &[u8],
+        _ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        Ok(Self(parts.method.clone()))
+    }
+}
+
+impl std::ops::Deref for Method {
+    type Target = tauri::http::Method;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for Method {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -6,8 +6,17 @@ use crate::CommandContext;
 
 mod app_handle;
 mod bytes;
+mod cancellation_token;
+mod deadline;
 mod header_map;
+mod json;
+mod method;
+mod or_default;
+mod path;
+mod progress;
+mod query;
 mod request;
+mod state;
 #[cfg(feature = "unstable")]
 mod webview;
 mod webview_window;
@@ -16,8 +25,17 @@ mod window;
 
 pub use app_handle::*;
 pub use bytes::*;
+pub use cancellation_token::*;
+pub use deadline::*;
 pub use header_map::*;
+pub use json::*;
+pub use method::*;
+pub use or_default::*;
+pub use path::*;
+pub use progress::*;
+pub use query::*;
 pub use request::*;
+pub use state::*;
 #[cfg(feature = "unstable")]
 pub use webview::*;
 pub use webview_window::*;
@@ -65,22 +83,23 @@ impl<T: FromRequestParts<R>, R: Runtime> FromRequest<R, private::ViaParts> for T
         ctx: &mut CommandContext<R>,
     ) -> crate::Result<Self> {
         let (mut parts, body) = req.into_parts();
-        T::from_request_parts(&mut parts, &body, ctx).await
+        let value = T::from_request_parts(&mut parts, &body, ctx).await?;
+        // the body wasn't consumed by the handler, any JSON argument left in it is a surplus
+        ctx.parse_unread_args(&body);
+        Ok(value)
     }
 }
 
 /// Blanket implementation to allow deserializing JSON body into any type
 /// that implements `DeserializeOwned`.
+///
+/// Missing trailing arguments are deserialized from `null`, so `Option<T>` arguments resolve to `None`.
 impl<R: Runtime, T: DeserializeOwned + Send + 'static> FromRequestParts<R> for T {
     async fn from_request_parts(
         _parts: &mut tauri::http::request::Parts,
         body: &[u8],
         ctx: &mut CommandContext<R>,
     ) -> crate::Result<Self> {
-        let arg = ctx.take_json_arg(body)?;
-        let deserialized = serde_json::from_value(arg).map_err(|e| {
-            crate::Error::DeserializationError(format!("JSON deserialization error: {}", e))
-        })?;
-        Ok(deserialized)
+        ctx.deserialize_json_arg(body)
     }
 }
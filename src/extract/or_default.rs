@@ -0,0 +1,53 @@
+use serde::de::DeserializeOwned;
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequestParts};
+
+/// A JSON argument Extractor that falls back to [Default::default] when the argument is missing or `null`.
+///
+/// Useful to add new trailing arguments to a command without breaking older callers.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{OrDefault, Router};
+///
+/// fn search(query: String, OrDefault(limit): OrDefault<u32>) -> String {
+///    format!("searching for {query} (limit {limit})")
+/// }
+///
+/// fn main() {
+///     // can be invoked with `["tauri"]` or `["tauri", 10]`
+///     let router: Router<_> = Router::new().command("search", search);
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+pub struct OrDefault<T>(pub T);
+
+impl<R: Runtime, T: DeserializeOwned + Default + Send> FromRequestParts<R> for OrDefault<T> {
+    async fn from_request_parts(
+        _parts: &mut tauri::http::request::Parts,
+        body: &[u8],
+        ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        match ctx.next_json_arg(body)? {
+            None | Some(serde_json::Value::Null) => Ok(Self(T::default())),
+            Some(arg) => ctx.deserialize_json_value(arg).map(Self),
+        }
+    }
+}
+
+impl<T> std::ops::Deref for OrDefault<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for OrDefault<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,275 @@
+use serde::de::{DeserializeOwned, IntoDeserializer};
+use serde::forward_to_deserialize_any;
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequestParts};
+
+/// A path parameters Extractor.
+///
+/// Deserializes the parameters captured by a command route such as `documents/:id/pages/:page`
+/// into a single value, a tuple or a struct.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{Path, Router};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Page {
+///     id: u32,
+///     page: u32,
+/// }
+///
+/// fn page(Path((id, page)): Path<(u32, u32)>) -> String {
+///    format!("document {id}, page {page}")
+/// }
+///
+/// fn page_struct(Path(page): Path<Page>) -> String {
+///    format!("document {}, page {}", page.id, page.page)
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new()
+///         .command("documents/:id/pages/:page", page)
+///         .command("v2/documents/:id/pages/:page", page_struct);
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+pub struct Path<T>(pub T);
+
+impl<R: Runtime, T: DeserializeOwned + Send> FromRequestParts<R> for Path<T> {
+    async fn from_request_parts(
+        _parts: &mut tauri::http::request::Parts,
+        _body: &[u8],
+        ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        // parameters are captured from the raw path, so that an encoded `/` stays in its segment
+        let params = ctx
+            .path_params
+            .iter()
+            .map(|(key, value)| {
+                let value = percent_encoding::percent_decode_str(value).decode_utf8_lossy();
+                (key.clone(), value.into_owned())
+            })
+            .collect::<Vec<_>>();
+        T::deserialize(PathDeserializer(&params))
+            .map(Self)
+            .map_err(|e| {
+                crate::Error::DeserializationError(format!("Path deserialization error: {}", e))
+            })
+    }
+}
+
+impl<T> std::ops::Deref for Path<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for Path<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+type Error = serde::de::value::Error;
+
+/// Deserializes all the path parameters as a map, a sequence or a single value.
+struct PathDeserializer<'de>(&'de [(String, String)]);
+
+impl<'de> PathDeserializer<'de> {
+    fn single(self) -> Result<ValueDeserializer<'de>, Error> {
+        match self.0 {
+            [(_, value)] => Ok(ValueDeserializer(value)),
+            params => Err(serde::de::Error::custom(format!(
+                "expected 1 path parameter, found {}",
+                params.len()
+            ))),
+        }
+    }
+}
+
+macro_rules! deserialize_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> serde::Deserializer<'de> for PathDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.0.len() == 1 {
+            self.single()?.deserialize_any(visitor)
+        } else {
+            self.deserialize_map(visitor)
+        }
+    }
+
+    fn deserialize_map<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(serde::de::value::MapDeserializer::new(
+            self.0
+                .iter()
+                .map(|(key, value)| (key.as_str(), ValueDeserializer(value))),
+        ))
+    }
+
+    fn deserialize_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(serde::de::value::SeqDeserializer::new(
+            self.0.iter().map(|(_, value)| ValueDeserializer(value)),
+        ))
+    }
+
+    fn deserialize_tuple<V: serde::de::Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_unit_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    deserialize_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_option deserialize_identifier
+    }
+}
+
+/// Deserializes a single path parameter, parsing it into the requested type.
+struct ValueDeserializer<'de>(&'de str);
+
+macro_rules! deserialize_parse {
+    ($($method:ident => $visit:ident($ty:ty),)*) => {
+        $(
+            fn $method<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let value = self.0.parse::<$ty>().map_err(|_| {
+                    serde::de::Error::custom(format!(
+                        "cannot parse `{}` as `{}`",
+                        self.0,
+                        stringify!($ty)
+                    ))
+                })?;
+                visitor.$visit(value)
+            }
+        )*
+    };
+}
+
+impl<'de> serde::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    deserialize_parse! {
+        deserialize_bool => visit_bool(bool),
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+        deserialize_char => visit_char(char),
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct
+        map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for ValueDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
@@ -0,0 +1,100 @@
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequestParts};
+
+/// Header of the frontend channel that receives the events of a [Progress] extractor.
+pub(crate) const PROGRESS_CHANNEL_HEADER: &str = "x-router-progress-channel";
+
+/// An Extractor to report typed progress events of a long-running command to its caller,
+/// delivered to the `onProgress` callback passed to `invokeWithOptions` through a Tauri channel.
+///
+/// Events are dropped when the caller didn't pass an `onProgress` callback.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{Progress, Router};
+///
+/// #[derive(Clone, serde::Serialize)]
+/// struct ImportProgress {
+///     percent: u8,
+/// }
+///
+/// async fn import(files: Vec<String>, progress: Progress<ImportProgress>) -> usize {
+///     for (i, _file) in files.iter().enumerate() {
+///         // import the file
+///         let percent = ((i + 1) * 100 / files.len()) as u8;
+///         let _ = progress.send(ImportProgress { percent });
+///     }
+///     files.len()
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().command("import", import);
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+pub struct Progress<T> {
+    channel: Option<Channel<InvokeResponseBody>>,
+    phantom: PhantomData<fn(T)>,
+}
+
+impl<T: Serialize> Progress<T> {
+    /// Sends a progress event to the caller.
+    pub fn send(&self, event: T) -> crate::Result<()> {
+        let Some(channel) = &self.channel else {
+            return Ok(());
+        };
+        let event = serde_json::to_string(&event)
+            .map_err(|e| crate::Error::SerializationError(e.to_string()))?;
+        channel
+            .send(InvokeResponseBody::Json(event))
+            .map_err(|e| crate::Error::Io(format!("failed to send progress: {e}")))
+    }
+
+    /// Returns whether the caller listens to progress events.
+    pub fn is_enabled(&self) -> bool {
+        self.channel.is_some()
+    }
+}
+
+impl<T> Clone for Progress<T> {
+    fn clone(&self) -> Self {
+        Self {
+            channel: self.channel.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Progress<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Progress")
+            .field("enabled", &self.channel.is_some())
+            .finish()
+    }
+}
+
+impl<R: Runtime, T: Serialize> FromRequestParts<R> for Progress<T> {
+    async fn from_request_parts(
+        parts: &mut tauri::http::request::Parts,
+        _body: &[u8],
+        ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        let channel = crate::stream::frontend_channel(
+            &ctx.app_handle,
+            &ctx.webview_label,
+            PROGRESS_CHANNEL_HEADER,
+            parts.headers.get(PROGRESS_CHANNEL_HEADER),
+        )?;
+        Ok(Self {
+            channel,
+            phantom: PhantomData,
+        })
+    }
+}
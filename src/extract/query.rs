@@ -0,0 +1,58 @@
+use serde::de::DeserializeOwned;
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequestParts};
+
+/// A query string Extractor.
+///
+/// Deserializes the URL-encoded query string of the request, e.g. `search?q=tauri&limit=5`.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{Query, Router};
+///
+/// #[derive(serde::Deserialize)]
+/// struct Search {
+///     q: String,
+///     limit: Option<u32>,
+/// }
+///
+/// fn search(Query(search): Query<Search>) -> String {
+///    format!("searching for {} (limit {:?})", search.q, search.limit)
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().command("search", search);
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+pub struct Query<T>(pub T);
+
+impl<R: Runtime, T: DeserializeOwned + Send> FromRequestParts<R> for Query<T> {
+    async fn from_request_parts(
+        _parts: &mut tauri::http::request::Parts,
+        _body: &[u8],
+        ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        let query = ctx.query.as_deref().unwrap_or_default();
+        serde_urlencoded::from_str(query).map(Self).map_err(|e| {
+            crate::Error::DeserializationError(format!("Query deserialization error: {}", e))
+        })
+    }
+}
+
+impl<T> std::ops::Deref for Query<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for Query<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,34 @@
+use tauri::Runtime;
+
+use crate::{CommandContext, FromRequestParts};
+
+/// A state Extractor.
+///
+/// Extracts state attached to the [Router](crate::Router) via [Router::with_state](crate::Router::with_state)
+/// or managed by Tauri via [tauri::Manager::manage].
+#[derive(Clone)]
+pub struct State<T>(pub T);
+
+impl<R: Runtime, T: Clone + Send + Sync + 'static> FromRequestParts<R> for State<T> {
+    async fn from_request_parts(
+        _parts: &mut tauri::http::request::Parts,
+        _body: &[u8],
+        ctx: &mut CommandContext<R>,
+    ) -> crate::Result<Self> {
+        ctx.state().map(Self)
+    }
+}
+
+impl<T> std::ops::Deref for State<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for State<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -42,7 +42,11 @@ where
 {
     type Future = std::future::Ready<tauri::http::Response<Vec<u8>>>;
 
-    fn call(self, _req: tauri::http::Request<Vec<u8>>, _ctx: CommandContext<R>) -> Self::Future {
+    fn call(self, req: tauri::http::Request<Vec<u8>>, mut ctx: CommandContext<R>) -> Self::Future {
+        ctx.parse_unread_args(req.body());
+        if let Err(error) = ctx.ensure_no_surplus_args() {
+            return std::future::ready(ctx.reject(error, None));
+        }
         std::future::ready(self().into_response())
     }
 }
@@ -57,7 +61,11 @@ where
 {
     type Future = Pin<Box<dyn Future<Output = tauri::http::Response<Vec<u8>>> + Send>>;
 
-    fn call(self, _req: tauri::http::Request<Vec<u8>>, _ctx: CommandContext<R>) -> Self::Future {
+    fn call(self, req: tauri::http::Request<Vec<u8>>, mut ctx: CommandContext<R>) -> Self::Future {
+        ctx.parse_unread_args(req.body());
+        if let Err(error) = ctx.ensure_no_surplus_args() {
+            return Box::pin(std::future::ready(ctx.reject(error, None)));
+        }
         Box::pin(async move { self().await.into_response() })
     }
 }
@@ -86,20 +94,26 @@ macro_rules! impl_command_handler {
                 Box::pin(async move {
                     let (mut parts, body) = req.into_parts();
 
+                    let mut arg_index = 0;
                     $(
                         let $ty = match $ty::from_request_parts(&mut parts, &body, &mut ctx).await {
                             Ok(value) => value,
-                            Err(error) => return crate::response::error(error),
+                            Err(error) => return ctx.reject(error, Some(arg_index)),
                         };
+                        arg_index += 1;
                     )*
 
                     let req = tauri::http::Request::from_parts(parts, body);
 
                     let $last = match $last::from_request(req, &mut ctx).await {
                         Ok(value) => value,
-                        Err(error) => return crate::response::error(error),
+                        Err(error) => return ctx.reject(error, Some(arg_index)),
                     };
 
+                    if let Err(error) = ctx.ensure_no_surplus_args() {
+                        return ctx.reject(error, None);
+                    }
+
                     self($($ty,)* $last).into_response()
                 })
             }
@@ -126,20 +140,26 @@ macro_rules! impl_command_handler {
                 Box::pin(async move {
                     let (mut parts, body) = req.into_parts();
 
+                    let mut arg_index = 0;
                     $(
                         let $ty = match $ty::from_request_parts(&mut parts, &body, &mut ctx).await {
                             Ok(value) => value,
-                            Err(error) => return crate::response::error(error),
+                            Err(error) => return ctx.reject(error, Some(arg_index)),
                         };
+                        arg_index += 1;
                     )*
 
                     let req = tauri::http::Request::from_parts(parts, body);
 
                     let $last = match $last::from_request(req, &mut ctx).await {
                         Ok(value) => value,
-                        Err(error) => return crate::response::error(error),
+                        Err(error) => return ctx.reject(error, Some(arg_index)),
                     };
 
+                    if let Err(error) = ctx.ensure_no_surplus_args() {
+                        return ctx.reject(error, None);
+                    }
+
                     self($($ty,)* $last).await.into_response()
                 })
             }
@@ -1,25 +1,37 @@
 use std::sync::Arc;
 
-use tauri::{
-    plugin::{Builder, TauriPlugin},
-    Runtime,
-};
+use tauri::{plugin::TauriPlugin, Runtime};
 
+mod acl;
+mod cancellation;
 mod context;
+mod cors;
 mod error;
 mod extract;
 mod handler;
+mod method_router;
+mod middleware;
+mod panic;
+mod range;
+mod rejection;
 mod response;
 mod router;
+mod stream;
 
 pub use context::*;
+pub use cors::*;
 pub use error::*;
 pub use extract::*;
 pub use handler::*;
+pub use method_router::*;
+pub use middleware::*;
+pub use range::*;
+pub use rejection::*;
 pub use response::*;
 pub use router::*;
+pub use stream::*;
 
-/// Initializes the plugin.
+/// Initializes the plugin with the default [CorsConfig].
 ///
 /// ## Example
 /// ```rust,no_run
@@ -51,46 +63,148 @@ pub use router::*;
 /// }
 /// ```
 pub fn init<R: Runtime>(router: Router<R>) -> TauriPlugin<R> {
-    let router = Arc::new(router);
-
-    Builder::new("router")
-        .register_asynchronous_uri_scheme_protocol("router", move |context, request, responder| {
-            use tauri::http::header::*;
-            use tauri::http::*;
-
-            let app_handle = context.app_handle().clone();
-            let webview_label = context.webview_label().to_string();
-            let router = Arc::clone(&router);
-
-            tauri::async_runtime::spawn(async move {
-                let response = match *request.method() {
-                    Method::OPTIONS => Response::builder()
-                        .header(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"))
-                        .header(ACCESS_CONTROL_ALLOW_HEADERS, HeaderValue::from_static("*"))
-                        .body(Vec::new())
-                        .unwrap(),
-
-                    Method::POST => {
-                        let mut response = router
-                            .handle_request(&app_handle, &webview_label, request)
-                            .await;
-                        let headers_mut = response.headers_mut();
-                        headers_mut
-                            .insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
-                        headers_mut
-                            .insert(ACCESS_CONTROL_ALLOW_HEADERS, HeaderValue::from_static("*"));
-                        response
-                    }
-
-                    _ => Response::builder()
-                        .status(StatusCode::METHOD_NOT_ALLOWED)
-                        .header(CONTENT_TYPE, "application/json")
-                        .body("only POST and OPTIONS are allowed".as_bytes().to_vec())
-                        .unwrap(),
-                };
-
-                responder.respond(response);
-            });
-        })
-        .build()
+    Builder::new(router).build()
+}
+
+/// Builder for the plugin, to configure it beyond [init].
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{Builder, CorsConfig, Router};
+///
+/// fn greet(name: String) -> String {
+///    format!("Hello, {}!", name)
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().command("greet", greet);
+///
+///     let app = tauri::Builder::default().plugin(
+///         Builder::new(router)
+///             .cors(CorsConfig::new().allow_origin("tauri://localhost"))
+///             .build(),
+///     );
+/// }
+/// ```
+pub struct Builder<R: Runtime> {
+    router: Router<R>,
+    cors: CorsConfig,
+    acl: bool,
+}
+
+impl<R: Runtime> Builder<R> {
+    /// Creates a new plugin builder for the given router.
+    pub fn new(router: Router<R>) -> Self {
+        Self {
+            router,
+            cors: CorsConfig::default(),
+            acl: false,
+        }
+    }
+
+    /// Sets the CORS and origin policy of the `router` protocol.
+    pub fn cors(mut self, cors: CorsConfig) -> Self {
+        self.cors = cors;
+        self
+    }
+
+    /// Enables checking every request against the capabilities granted to the calling webview,
+    /// like regular Tauri commands. Disabled by default.
+    ///
+    /// A route is checked as the `plugin:router|{route}` command, so `files/:id` is checked as
+    /// `plugin:router|files/:id`, and the fallback handler as `plugin:router|/fallback`.
+    /// Requests without a granted permission are rejected with [Error::Forbidden].
+    ///
+    /// The permissions allowing these commands are defined by the app, in a permission file
+    /// of its `permissions` directory, and granted to webviews by its capabilities.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::{Builder, Router};
+    ///
+    /// fn greet(name: String) -> String {
+    ///    format!("Hello, {}!", name)
+    /// }
+    ///
+    /// fn main() {
+    ///     // requires a permission allowing the `plugin:router|greet` command
+    ///     let router: Router<_> = Router::new().command("greet", greet);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(Builder::new(router).acl(true).build());
+    /// }
+    /// ```
+    pub fn acl(mut self, enabled: bool) -> Self {
+        self.acl = enabled;
+        self
+    }
+
+    /// Builds the plugin.
+    pub fn build(mut self) -> TauriPlugin<R> {
+        self.router.acl = self.acl;
+        let router = Arc::new(self.router);
+        let cors = Arc::new(self.cors);
+
+        tauri::plugin::Builder::new("router")
+            .register_asynchronous_uri_scheme_protocol(
+                "router",
+                move |context, request, responder| {
+                    let app_handle = context.app_handle().clone();
+                    let webview_label = context.webview_label().to_string();
+                    let router = Arc::clone(&router);
+                    let cors = Arc::clone(&cors);
+
+                    tauri::async_runtime::spawn(async move {
+                        let response = handle_protocol_request(
+                            &router,
+                            &cors,
+                            &app_handle,
+                            &webview_label,
+                            request,
+                        )
+                        .await;
+                        responder.respond(response);
+                    });
+                },
+            )
+            .build()
+    }
+}
+
+/// Handles a request to the `router` protocol, applying the CORS policy around [Router::handle_request].
+pub(crate) async fn handle_protocol_request<R: Runtime>(
+    router: &Router<R>,
+    cors: &CorsConfig,
+    app_handle: &tauri::AppHandle<R>,
+    webview_label: &str,
+    request: tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    use tauri::http::*;
+
+    let (command, _) = crate::router::command_from_uri(request.uri());
+    let allow_origin = match cors.check_origin(request.method(), &command, request.headers()) {
+        Ok(allow_origin) => allow_origin,
+        Err(error) => return crate::response::error(error),
+    };
+
+    let mut response = match *request.method() {
+        Method::OPTIONS => {
+            let mut response = Response::new(Vec::new());
+            cors.apply_preflight(response.headers_mut());
+            response
+        }
+
+        ref method if cors.is_method_allowed(method) => {
+            router
+                .handle_request(app_handle, webview_label, request)
+                .await
+        }
+
+        ref method => crate::response::error(crate::Error::MethodNotAllowed(format!(
+            "`{method}` is not allowed by the CORS policy"
+        ))),
+    };
+
+    cors.apply(allow_origin, response.headers_mut());
+    response
 }
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use tauri::http::{header::ALLOW, HeaderValue, Method};
+use tauri::Runtime;
+
+use crate::{CommandHandler, ErasedCommandHandler};
+
+/// Handlers of a route for each HTTP method, registered with [Router::route](crate::Router::route).
+///
+/// `HEAD` requests are handled by the `GET` handler without a response body,
+/// unless a `HEAD` handler is registered. Requests with any other method are rejected
+/// with [Error::MethodNotAllowed](crate::Error::MethodNotAllowed).
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{get, Path, Router};
+///
+/// fn read(Path(id): Path<u32>) -> String {
+///    format!("document {id}")
+/// }
+///
+/// fn delete(Path(id): Path<u32>) -> bool {
+///    true
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().route("documents/:id", get(read).delete(delete));
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+pub struct MethodRouter<R: Runtime> {
+    handlers: Vec<(Method, ErasedCommandHandler<R>)>,
+}
+
+impl<R: Runtime> Default for MethodRouter<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+macro_rules! method_fns {
+    ($($name:ident => $method:ident,)*) => {
+        impl<R: Runtime> MethodRouter<R> {
+            $(
+                #[doc = concat!("Routes `", stringify!($method), "` requests to the given handler.")]
+                pub fn $name<H, T>(self, handler: H) -> Self
+                where
+                    H: CommandHandler<R, T>,
+                {
+                    self.on(Method::$method, handler)
+                }
+            )*
+        }
+
+        $(
+            #[doc = concat!("Creates a [MethodRouter] routing `", stringify!($method), "` requests to the given handler.")]
+            pub fn $name<R, H, T>(handler: H) -> MethodRouter<R>
+            where
+                R: Runtime,
+                H: CommandHandler<R, T>,
+            {
+                MethodRouter::new().on(Method::$method, handler)
+            }
+        )*
+    };
+}
+
+method_fns! {
+    get => GET,
+    head => HEAD,
+    post => POST,
+    put => PUT,
+    patch => PATCH,
+    delete => DELETE,
+}
+
+/// Creates a [MethodRouter] routing requests with the given method to the given handler.
+pub fn on<R, H, T>(method: Method, handler: H) -> MethodRouter<R>
+where
+    R: Runtime,
+    H: CommandHandler<R, T>,
+{
+    MethodRouter::new().on(method, handler)
+}
+
+impl<R: Runtime> MethodRouter<R> {
+    /// Creates a new method router without any handler.
+    pub fn new() -> Self {
+        Self {
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Routes requests with the given method to the given handler.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a handler is already registered for the method.
+    pub fn on<H, T>(self, method: Method, handler: H) -> Self
+    where
+        H: CommandHandler<R, T>,
+    {
+        let erased: ErasedCommandHandler<R> =
+            Arc::new(move |ctx, req| Box::pin(handler.clone().call(req, ctx)));
+        self.on_erased(method, erased)
+    }
+
+    /// Routes requests with the given method to an already erased handler.
+    pub(crate) fn on_erased(mut self, method: Method, handler: ErasedCommandHandler<R>) -> Self {
+        if self.handlers.iter().any(|(m, _)| *m == method) {
+            panic!("conflicting method: a `{method}` handler is already registered");
+        }
+        self.handlers.push((method, handler));
+        self
+    }
+
+    /// Converts the method router into a handler dispatching on the request method.
+    pub(crate) fn into_handler(self) -> ErasedCommandHandler<R> {
+        let mut allowed = self
+            .handlers
+            .iter()
+            .map(|(method, _)| method.as_str())
+            .collect::<Vec<_>>();
+        let has_get = self
+            .handlers
+            .iter()
+            .any(|(method, _)| method == Method::GET);
+        let has_head = self
+            .handlers
+            .iter()
+            .any(|(method, _)| method == Method::HEAD);
+        if has_get && !has_head {
+            allowed.push(Method::HEAD.as_str());
+        }
+        let allowed = allowed.join(", ");
+        let handlers = self.handlers;
+
+        Arc::new(move |ctx, req| {
+            let handler = handlers
+                .iter()
+                .find(|(method, _)| method == req.method())
+                .map(|(_, handler)| handler);
+            if let Some(handler) = handler {
+                return handler(ctx, req);
+            }
+
+            // fall back to the `GET` handler for `HEAD` requests, without the body
+            let get = handlers
+                .iter()
+                .find(|(method, _)| method == Method::GET)
+                .map(|(_, handler)| Arc::clone(handler));
+            if let (Some(get), true) = (get, req.method() == Method::HEAD) {
+                return Box::pin(async move {
+                    let mut response = get(ctx, req).await;
+                    response.body_mut().clear();
+                    response
+                });
+            }
+
+            let error = crate::Error::MethodNotAllowed(format!(
+                "`{}` is not allowed, expected one of: {allowed}",
+                req.method()
+            ));
+            let mut response = crate::response::error(error);
+            if let Ok(allowed) = HeaderValue::from_str(&allowed) {
+                response.headers_mut().insert(ALLOW, allowed);
+            }
+            Box::pin(std::future::ready(response))
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use tauri::Runtime;
+
+use crate::{CommandContext, ErasedCommandHandler};
+
+/// The rest of the middleware chain, ending with the command handler.
+pub struct Next<R: Runtime> {
+    pub(crate) handler: ErasedCommandHandler<R>,
+}
+
+impl<R: Runtime> Next<R> {
+    /// Runs the rest of the middleware chain and the command handler.
+    pub async fn run(
+        self,
+        ctx: CommandContext<R>,
+        req: tauri::http::Request<Vec<u8>>,
+    ) -> tauri::http::Response<Vec<u8>> {
+        (self.handler)(ctx, req).await
+    }
+}
+
+/// Trait for middleware that wraps command handlers.
+///
+/// Middleware can inspect the [CommandContext] and the request before calling [Next::run],
+/// or short-circuit by returning its own response without calling it.
+///
+/// This trait is implemented for async functions and closures with the signature
+/// `Fn(CommandContext<R>, tauri::http::Request<Vec<u8>>, Next<R>) -> impl Future<Output = tauri::http::Response<Vec<u8>>>`.
+pub trait Middleware<R: Runtime>: Send + Sync + 'static {
+    /// Calls the middleware.
+    fn call(
+        &self,
+        ctx: CommandContext<R>,
+        req: tauri::http::Request<Vec<u8>>,
+        next: Next<R>,
+    ) -> Pin<Box<dyn Future<Output = tauri::http::Response<Vec<u8>>> + Send>>;
+}
+
+impl<R, F, Fut> Middleware<R> for F
+where
+    R: Runtime,
+    F: Fn(CommandContext<R>, tauri::http::Request<Vec<u8>>, Next<R>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = tauri::http::Response<Vec<u8>>> + Send + 'static,
+{
+    fn call(
+        &self,
+        ctx: CommandContext<R>,
+        req: tauri::http::Request<Vec<u8>>,
+        next: Next<R>,
+    ) -> Pin<Box<dyn Future<Output = tauri::http::Response<Vec<u8>>> + Send>> {
+        Box::pin(self(ctx, req, next))
+    }
+}
+
+/// A [tower_service::Service] that calls a command handler, used as the inner service of tower layers.
+///
+/// The [CommandContext] of the request is available in the request extensions.
+#[cfg(feature = "tower")]
+pub struct HandlerService<R: Runtime> {
+    pub(crate) handler: ErasedCommandHandler<R>,
+}
+
+#[cfg(feature = "tower")]
+impl<R: Runtime> Clone for HandlerService<R> {
+    fn clone(&self) -> Self {
+        Self {
+            handler: std::sync::Arc::clone(&self.handler),
+        }
+    }
+}
+
+#[cfg(feature = "tower")]
+impl<R: Runtime> tower_service::Service<tauri::http::Request<Vec<u8>>> for HandlerService<R> {
+    type Response = tauri::http::Response<Vec<u8>>;
+    type Error = std::convert::Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: tauri::http::Request<Vec<u8>>) -> Self::Future {
+        let handler = std::sync::Arc::clone(&self.handler);
+        Box::pin(async move {
+            let Some(ctx) = req.extensions_mut().remove::<CommandContext<R>>() else {
+                let error = crate::Error::MiddlewareError(
+                    "command context was removed from the request extensions".to_string(),
+                );
+                return Ok(crate::response::error(error));
+            };
+
+            Ok(handler(ctx, req).await)
+        })
+    }
+}
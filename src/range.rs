@@ -0,0 +1,388 @@
+use std::io::SeekFrom;
+use std::path::{Component, PathBuf};
+
+use tauri::http::{header::*, HeaderMap, HeaderValue, Method, StatusCode};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::IntoResponse;
+
+/// Default maximum size of a partial response, see [FileResponse::chunk_size].
+const DEFAULT_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Default maximum size of a whole file response, see [FileResponse::max_size].
+const DEFAULT_MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// A file response honouring the `Range` and `If-Range` request headers,
+/// for seeking in videos or resuming downloads of large local files.
+///
+/// The file is read lazily by the router after the command returns, only the requested range is read
+/// and sent with a `206 Partial Content` status and a `Content-Range` header.
+/// Middleware see the response headers but an empty body.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{get, FileResponse, Path, Router};
+///
+/// fn recording(Path(name): Path<String>) -> FileResponse {
+///     // `name` comes from the frontend, it can't escape the recordings directory
+///     FileResponse::from_dir("/recordings", name).content_type("video/mp4")
+/// }
+///
+/// fn main() {
+///     // loaded by `<video src="...">`
+///     let router: Router<_> = Router::new().route("recordings/*name", get(recording));
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileResponse {
+    path: crate::Result<PathBuf>,
+    content_type: Option<String>,
+    disposition: Option<String>,
+    chunk_size: u64,
+    max_size: u64,
+}
+
+impl FileResponse {
+    /// Creates a response with the content of the file at the given path.
+    ///
+    /// The `Content-Type` is guessed from the file extension, `application/octet-stream` by default.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self::with_path(Ok(path.into()))
+    }
+
+    /// Creates a response with the content of the file at the given relative path inside a directory.
+    ///
+    /// Paths received from the frontend, such as a `*path` route parameter, can contain `..`
+    /// or be absolute. Such paths are rejected with [Error::Forbidden](crate::Error::Forbidden)
+    /// instead of reading a file outside of the directory. Symbolic links inside the directory are followed.
+    pub fn from_dir(dir: impl Into<PathBuf>, path: impl AsRef<std::path::Path>) -> Self {
+        let path = path.as_ref();
+        let path = if path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            Ok(dir.into().join(path))
+        } else {
+            Err(crate::Error::Forbidden(format!(
+                "`{}` is not a relative path inside the directory",
+                path.display()
+            )))
+        };
+        Self::with_path(path)
+    }
+
+    fn with_path(path: crate::Result<PathBuf>) -> Self {
+        Self {
+            path,
+            content_type: None,
+            disposition: None,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_size: DEFAULT_MAX_SIZE,
+        }
+    }
+
+    /// Sets the `Content-Type` of the response, such as `video/mp4`.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Downloads the response as a file with the given name, instead of displaying it.
+    pub fn attachment(mut self, filename: impl AsRef<str>) -> Self {
+        self.disposition = Some(crate::response::content_disposition(
+            "attachment",
+            filename.as_ref(),
+        ));
+        self
+    }
+
+    /// Sets the maximum size of a partial response, 8 MiB by default.
+    ///
+    /// Larger ranges, such as the `bytes=0-` range requested by media elements,
+    /// are truncated and the client requests the rest as needed.
+    /// Requests without a `Range` header receive the whole file, see [FileResponse::max_size].
+    pub fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Sets the maximum size of a file sent as a whole, 64 MiB by default.
+    ///
+    /// The whole file is read into memory for requests without a `Range` header,
+    /// or with a stale `If-Range` validator. Such requests for larger files are rejected
+    /// with [Error::PayloadTooLarge](crate::Error::PayloadTooLarge), ranges are still served.
+    pub fn max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+}
+
+impl IntoResponse for FileResponse {
+    fn into_response(self) -> tauri::http::Response<Vec<u8>> {
+        let path = match self.path {
+            Ok(path) => path,
+            Err(error) => return crate::response::error(error),
+        };
+        let content_type = self.content_type.unwrap_or_else(|| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            tauri::utils::mime_type::MimeType::parse_from_uri_with_fallback(
+                &name,
+                tauri::utils::mime_type::MimeType::OctetStream,
+            )
+            .to_string()
+        });
+
+        ranged_response(
+            content_type,
+            self.disposition,
+            RangedBody::File {
+                path,
+                chunk_size: self.chunk_size,
+                max_size: self.max_size,
+            },
+        )
+    }
+}
+
+/// An in-memory binary response honouring the `Range` request header, see [FileResponse].
+///
+/// `If-Range` requests always receive the whole body, as it has no validator.
+#[derive(Debug, Clone)]
+pub struct RangedBytes {
+    body: Vec<u8>,
+    content_type: String,
+}
+
+impl RangedBytes {
+    /// Creates a ranged response, sent as `application/octet-stream` by default.
+    pub fn new(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            body: body.into(),
+            content_type: "application/octet-stream".to_string(),
+        }
+    }
+
+    /// Sets the `Content-Type` of the response.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = content_type.into();
+        self
+    }
+}
+
+impl IntoResponse for RangedBytes {
+    fn into_response(self) -> tauri::http::Response<Vec<u8>> {
+        ranged_response(self.content_type, None, RangedBody::Bytes(self.body))
+    }
+}
+
+/// Body of a ranged response, attached as a response extension and resolved by the router.
+#[derive(Debug, Clone)]
+pub(crate) enum RangedBody {
+    File {
+        path: PathBuf,
+        chunk_size: u64,
+        max_size: u64,
+    },
+    Bytes(Vec<u8>),
+}
+
+fn ranged_response(
+    content_type: String,
+    disposition: Option<String>,
+    body: RangedBody,
+) -> tauri::http::Response<Vec<u8>> {
+    let content_type = HeaderValue::from_str(&content_type)
+        .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+    let mut response = tauri::http::Response::builder()
+        .header(CONTENT_TYPE, content_type)
+        .header(ACCEPT_RANGES, "bytes");
+    if let Some(disposition) = disposition.and_then(|d| HeaderValue::from_str(&d).ok()) {
+        response = response.header(CONTENT_DISPOSITION, disposition);
+    }
+    response.extension(body).body(Vec::new()).unwrap()
+}
+
+/// The `Range` and `If-Range` headers of a request, captured before it is passed to the handler.
+pub(crate) struct RangeHeaders {
+    method: Method,
+    range: Option<HeaderValue>,
+    if_range: Option<HeaderValue>,
+}
+
+impl RangeHeaders {
+    pub(crate) fn new(method: &Method, headers: &HeaderMap) -> Self {
+        Self {
+            method: method.clone(),
+            range: headers.get(RANGE).cloned(),
+            if_range: headers.get(IF_RANGE).cloned(),
+        }
+    }
+
+    /// Reads the requested range of a [RangedBody] attached to the response, if any.
+    pub(crate) async fn resolve(
+        &self,
+        mut response: tauri::http::Response<Vec<u8>>,
+    ) -> tauri::http::Response<Vec<u8>> {
+        let Some(body) = response.extensions_mut().remove::<RangedBody>() else {
+            return response;
+        };
+
+        match self.read(body, response.headers_mut()).await {
+            Ok((status, body)) => {
+                *response.status_mut() = status;
+                *response.body_mut() = body;
+                response
+            }
+            Err((error, len)) => {
+                let mut response = crate::response::error(error);
+                if let Some(len) = len {
+                    response
+                        .headers_mut()
+                        .insert(CONTENT_RANGE, format!("bytes */{len}").parse().unwrap());
+                }
+                response
+            }
+        }
+    }
+
+    async fn read(
+        &self,
+        body: RangedBody,
+        headers: &mut HeaderMap,
+    ) -> std::result::Result<(StatusCode, Vec<u8>), (crate::Error, Option<u64>)> {
+        let (len, etag, chunk_size, max_size) = match &body {
+            RangedBody::File {
+                path,
+                chunk_size,
+                max_size,
+            } => {
+                let metadata = tokio::fs::metadata(path)
+                    .await
+                    .map_err(|e| (io_error(path, e), None))?;
+                (metadata.len(), file_etag(&metadata), *chunk_size, *max_size)
+            }
+            RangedBody::Bytes(bytes) => (bytes.len() as u64, None, u64::MAX, u64::MAX),
+        };
+        if let Some(etag) = &etag {
+            headers.insert(ETAG, etag.clone());
+        }
+
+        // a stale `If-Range` validator means the whole representation must be sent
+        let fresh = match &self.if_range {
+            Some(if_range) => etag.as_ref().is_some_and(|etag| etag == if_range),
+            None => true,
+        };
+        let range = match &self.range {
+            Some(range) if fresh => parse_range(range.to_str().unwrap_or_default(), len),
+            _ => None,
+        };
+
+        let (status, start, end) = match range {
+            Some(Ok((start, end))) => {
+                let end = end.min(start.saturating_add(chunk_size) - 1);
+                let content_range = format!("bytes {start}-{end}/{len}");
+                headers.insert(CONTENT_RANGE, content_range.parse().unwrap());
+                (StatusCode::PARTIAL_CONTENT, start, end + 1)
+            }
+            Some(Err(())) => {
+                let error = crate::Error::RangeNotSatisfiable(format!(
+                    "`{}` is outside of the {len} bytes of the response",
+                    String::from_utf8_lossy(self.range.as_ref().unwrap().as_bytes())
+                ));
+                return Err((error, Some(len)));
+            }
+            None if len > max_size => {
+                let error = crate::Error::PayloadTooLarge(format!(
+                    "the response is {len} bytes, more than the maximum of {max_size} bytes sent without a `Range` header"
+                ));
+                return Err((error, None));
+            }
+            None => (StatusCode::OK, 0, len),
+        };
+        headers.insert(CONTENT_LENGTH, (end - start).into());
+
+        if self.method == Method::HEAD {
+            return Ok((status, Vec::new()));
+        }
+
+        let body = match body {
+            RangedBody::File { path, .. } => {
+                let read = async {
+                    let mut file = tokio::fs::File::open(&path).await?;
+                    file.seek(SeekFrom::Start(start)).await?;
+                    let mut buf = vec![0; (end - start) as usize];
+                    file.read_exact(&mut buf).await?;
+                    Ok(buf)
+                };
+                read.await.map_err(|e| (io_error(&path, e), None))?
+            }
+            RangedBody::Bytes(mut bytes) => {
+                bytes.truncate(end as usize);
+                bytes.drain(..start as usize);
+                bytes
+            }
+        };
+        Ok((status, body))
+    }
+}
+
+fn io_error(path: &std::path::Path, error: std::io::Error) -> crate::Error {
+    let message = format!("{}: {error}", path.display());
+    match error.kind() {
+        std::io::ErrorKind::NotFound => crate::Error::FileNotFound(message),
+        _ => crate::Error::Io(message),
+    }
+}
+
+/// Creates a strong `ETag` from the size and modification time of a file.
+fn file_etag(metadata: &std::fs::Metadata) -> Option<HeaderValue> {
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    let etag = format!(
+        "\"{:x}-{:x}-{:x}\"",
+        metadata.len(),
+        modified.as_secs(),
+        modified.subsec_nanos()
+    );
+    HeaderValue::from_str(&etag).ok()
+}
+
+/// Parses a single `bytes` range into inclusive bounds, clamped to the length of the body.
+///
+/// Returns `None` for invalid or multiple ranges, which are ignored,
+/// and `Some(Err(()))` for unsatisfiable ranges.
+fn parse_range(range: &str, len: u64) -> Option<std::result::Result<(u64, u64), ()>> {
+    let spec = range.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        let suffix = end.parse::<u64>().ok()?;
+        if suffix == 0 || len == 0 {
+            return Some(Err(()));
+        }
+        return Some(Ok((len.saturating_sub(suffix), len - 1)));
+    }
+
+    let start = start.parse::<u64>().ok()?;
+    let end = match end {
+        "" => None,
+        end => Some(end.parse::<u64>().ok()?),
+    };
+    if end.is_some_and(|end| end < start) {
+        return None;
+    }
+    if start >= len {
+        return Some(Err(()));
+    }
+    Some(Ok((start, end.map_or(len - 1, |end| end.min(len - 1)))))
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+/// Hook converting a [Rejection] into the response sent to the frontend.
+pub(crate) type RejectionHandler =
+    Arc<dyn Fn(Rejection) -> tauri::http::Response<Vec<u8>> + Send + Sync>;
+
+/// An extractor failure, passed to the hook set with [Router::on_rejection](crate::Router::on_rejection).
+#[derive(Debug)]
+pub struct Rejection {
+    pub(crate) command: String,
+    pub(crate) arg_index: Option<usize>,
+    pub(crate) error: crate::Error,
+}
+
+impl Rejection {
+    /// Returns the command that rejected the request, as registered, such as `documents/:id`.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Returns the position of the failed extractor in the handler arguments,
+    /// or `None` if the request was rejected after all extractors succeeded,
+    /// such as for surplus JSON arguments.
+    pub fn arg_index(&self) -> Option<usize> {
+        self.arg_index
+    }
+
+    /// Returns the error of the extractor.
+    pub fn error(&self) -> &crate::Error {
+        &self.error
+    }
+
+    /// Returns the error of the extractor, consuming the rejection.
+    pub fn into_error(self) -> crate::Error {
+        self.error
+    }
+}
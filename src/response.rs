@@ -13,11 +13,128 @@ impl IntoResponse for Response {
     }
 }
 
+/// A binary response with its `Content-Type`, `Content-Length` and `Content-Disposition` headers,
+/// for commands whose URL is loaded directly by `<img>`, `<video>` or `<a download>` elements.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{get, Binary, Path, Router};
+///
+/// fn thumbnail(Path(id): Path<u32>) -> Binary {
+///     let png = vec![/* ... */];
+///     Binary::new(png).content_type("image/png")
+/// }
+///
+/// fn export(Path(id): Path<u32>) -> Binary {
+///     Binary::new(b"a,b,c".to_vec())
+///         .content_type("text/csv")
+///         .attachment(format!("export-{id}.csv"))
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new()
+///         .route("thumbnails/:id", get(thumbnail))
+///         .route("exports/:id", get(export));
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Binary {
+    body: Vec<u8>,
+    content_type: String,
+    disposition: Option<String>,
+}
+
+impl Binary {
+    /// Creates a binary response, sent as `application/octet-stream` by default.
+    pub fn new(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            body: body.into(),
+            content_type: "application/octet-stream".to_string(),
+            disposition: None,
+        }
+    }
+
+    /// Sets the `Content-Type` of the response, such as `image/png`.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = content_type.into();
+        self
+    }
+
+    /// Displays the response inline with the given file name, used when it is saved by the user.
+    pub fn inline(mut self, filename: impl AsRef<str>) -> Self {
+        self.disposition = Some(content_disposition("inline", filename.as_ref()));
+        self
+    }
+
+    /// Downloads the response as a file with the given name, instead of displaying it.
+    pub fn attachment(mut self, filename: impl AsRef<str>) -> Self {
+        self.disposition = Some(content_disposition("attachment", filename.as_ref()));
+        self
+    }
+}
+
+impl IntoResponse for Binary {
+    fn into_response(self) -> tauri::http::Response<Vec<u8>> {
+        use tauri::http::header::*;
+
+        let mut response = tauri::http::Response::builder()
+            .header(CONTENT_LENGTH, self.body.len())
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_str(&self.content_type)
+                    .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+            );
+        if let Some(disposition) = self
+            .disposition
+            .and_then(|d| HeaderValue::from_str(&d).ok())
+        {
+            response = response.header(CONTENT_DISPOSITION, disposition);
+        }
+        response.body(self.body).unwrap()
+    }
+}
+
+/// Formats a `Content-Disposition` header value, with an ASCII fallback of non-ASCII file names.
+pub(crate) fn content_disposition(disposition: &str, filename: &str) -> String {
+    let fallback = filename
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c.is_ascii() && !c.is_ascii_control() => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    if fallback == filename {
+        return format!("{disposition}; filename=\"{filename}\"");
+    }
+
+    let encoded =
+        percent_encoding::utf8_percent_encode(filename, percent_encoding::NON_ALPHANUMERIC);
+    format!("{disposition}; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
+}
+
+/// Serializes the type into a JSON response.
+///
+/// A [Result] is serialized without its enum wrapper, `Ok` values are sent as-is
+/// and `Err` values are sent with a [500](tauri::http::StatusCode::INTERNAL_SERVER_ERROR) status code.
+/// An [AppError](crate::AppError), returned directly or as an `Err` value, is sent with its own status code.
 impl<T: serde::Serialize> IntoResponse for T {
     fn into_response(self) -> tauri::http::Response<Vec<u8>> {
-        match serde_json::to_vec(&self) {
+        let (status, body) = match self.serialize(ResultSerializer { top_level: true }) {
+            Ok((status, body)) => (status, Ok(body)),
+            Err(ResultSerializerError::Serialization(e)) => (tauri::http::StatusCode::OK, Err(e)),
+            Err(ResultSerializerError::NotAResult) => (
+                tauri::http::StatusCode::OK,
+                serde_json::to_vec(&self).map_err(|e| e.to_string()),
+            ),
+        };
+
+        match body {
             Ok(body) => tauri::http::Response::builder()
-                .status(200)
+                .status(status)
                 .header("Content-Type", "application/json")
                 .body(body)
                 .unwrap(),
@@ -30,6 +147,34 @@ impl<T: serde::Serialize> IntoResponse for T {
     }
 }
 
+/// Name of the newtype struct an [AppError](crate::AppError) serializes as.
+pub(crate) const APP_ERROR_NAME: &str = "$tauri_plugin_router::AppError";
+
+thread_local! {
+    /// Status of the [AppError](crate::AppError) being serialized on this thread, if any.
+    static APP_ERROR_STATUS: std::cell::Cell<Option<tauri::http::StatusCode>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Serializes an [AppError](crate::AppError) with `serialize`, exposing its status to the response
+/// serializer for the duration of the call only, so that it never outlives the error.
+pub(crate) fn with_app_error_status<T>(
+    status: tauri::http::StatusCode,
+    serialize: impl FnOnce() -> T,
+) -> T {
+    /// Restores the status of an enclosing error, even if `serialize` panics.
+    struct Restore(Option<tauri::http::StatusCode>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            APP_ERROR_STATUS.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(APP_ERROR_STATUS.with(|cell| cell.replace(Some(status))));
+    serialize()
+}
+
 /// Creates an error HTTP response from a crate::Error.
 pub(crate) fn error(error: crate::Error) -> tauri::http::Response<Vec<u8>> {
     tauri::http::Response::builder()
@@ -38,3 +183,139 @@ pub(crate) fn error(error: crate::Error) -> tauri::http::Response<Vec<u8>> {
         .body(serde_json::to_vec(&error).unwrap_or_default())
         .unwrap()
 }
+
+/// A [serde::Serializer] that only accepts a top-level [Result] or [AppError](crate::AppError),
+/// and serializes it to JSON with its status code, without the enum wrapper.
+struct ResultSerializer {
+    /// Whether a [Result] is accepted, `Err` values are only checked for an [AppError](crate::AppError).
+    top_level: bool,
+}
+
+#[derive(Debug)]
+enum ResultSerializerError {
+    /// The value is not a [Result], it should be serialized as is.
+    NotAResult,
+    /// The `Ok` or `Err` value failed to serialize.
+    Serialization(String),
+}
+
+impl std::fmt::Display for ResultSerializerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotAResult => f.write_str("not a result"),
+            Self::Serialization(e) => f.write_str(e),
+        }
+    }
+}
+
+impl std::error::Error for ResultSerializerError {}
+
+impl serde::ser::Error for ResultSerializerError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Serialization(msg.to_string())
+    }
+}
+
+macro_rules! not_a_result {
+    ($($method:ident($($ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            fn $method(self, $(_: $ty),*) -> std::result::Result<$ret, Self::Error> {
+                Err(ResultSerializerError::NotAResult)
+            }
+        )*
+    };
+}
+
+impl serde::Serializer for ResultSerializer {
+    type Ok = (tauri::http::StatusCode, Vec<u8>);
+    type Error = ResultSerializerError;
+
+    type SerializeSeq = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = serde::ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
+
+    not_a_result! {
+        serialize_bool(bool) -> Self::Ok;
+        serialize_i8(i8) -> Self::Ok;
+        serialize_i16(i16) -> Self::Ok;
+        serialize_i32(i32) -> Self::Ok;
+        serialize_i64(i64) -> Self::Ok;
+        serialize_u8(u8) -> Self::Ok;
+        serialize_u16(u16) -> Self::Ok;
+        serialize_u32(u32) -> Self::Ok;
+        serialize_u64(u64) -> Self::Ok;
+        serialize_f32(f32) -> Self::Ok;
+        serialize_f64(f64) -> Self::Ok;
+        serialize_char(char) -> Self::Ok;
+        serialize_str(&str) -> Self::Ok;
+        serialize_bytes(&[u8]) -> Self::Ok;
+        serialize_none() -> Self::Ok;
+        serialize_unit() -> Self::Ok;
+        serialize_unit_struct(&'static str) -> Self::Ok;
+        serialize_unit_variant(&'static str, u32, &'static str) -> Self::Ok;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: ?Sized + serde::Serialize>(
+        self,
+        _value: &T,
+    ) -> std::result::Result<Self::Ok, Self::Error> {
+        Err(ResultSerializerError::NotAResult)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> std::result::Result<Self::Ok, Self::Error> {
+        if name != APP_ERROR_NAME {
+            return Err(ResultSerializerError::NotAResult);
+        }
+        let status = APP_ERROR_STATUS
+            .with(|cell| cell.get())
+            .ok_or(ResultSerializerError::NotAResult)?;
+
+        let body = serde_json::to_vec(value)
+            .map_err(|e| ResultSerializerError::Serialization(e.to_string()))?;
+        Ok((status, body))
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> std::result::Result<Self::Ok, Self::Error> {
+        if name != "Result" || !self.top_level {
+            return Err(ResultSerializerError::NotAResult);
+        }
+
+        match variant {
+            "Ok" => {
+                let body = serde_json::to_vec(value)
+                    .map_err(|e| ResultSerializerError::Serialization(e.to_string()))?;
+                Ok((tauri::http::StatusCode::OK, body))
+            }
+            "Err" => match value.serialize(ResultSerializer { top_level: false }) {
+                Err(ResultSerializerError::NotAResult) => {
+                    let body = serde_json::to_vec(value)
+                        .map_err(|e| ResultSerializerError::Serialization(e.to_string()))?;
+                    Ok((tauri::http::StatusCode::INTERNAL_SERVER_ERROR, body))
+                }
+                result => result,
+            },
+            _ => Err(ResultSerializerError::NotAResult),
+        }
+    }
+}
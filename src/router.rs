@@ -1,12 +1,40 @@
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use tauri::Runtime;
 
-use crate::{CommandContext, CommandHandler, ErasedCommandHandler};
+use crate::cancellation::{Cancellations, CANCEL_COMMAND, REQUEST_ID_HEADER};
+use crate::panic::{panic_message, CatchUnwind, PanicHook};
+use crate::rejection::RejectionHandler;
+use crate::{
+    CommandContext, CommandHandler, ErasedCommandHandler, IntoResponse, MethodRouter, Middleware,
+    Next, Rejection,
+};
+
+/// Type map of the state attached to a [Router].
+pub(crate) type StateMap = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
 
 /// The router that holds command handlers and dispatches requests.
 pub struct Router<R: Runtime> {
     pub(crate) commands: HashMap<String, ErasedCommandHandler<R>>,
+    pub(crate) matcher: matchit::Router<String>,
+    pub(crate) state: Arc<StateMap>,
+    pub(crate) lenient_args: Option<bool>,
+    pub(crate) lenient_commands: HashMap<String, bool>,
+    pub(crate) acl: bool,
+    pub(crate) max_body_size: Option<usize>,
+    pub(crate) body_limits: HashMap<String, usize>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) timeouts: HashMap<String, Duration>,
+    pub(crate) webview_patterns: HashMap<String, Vec<Arc<[String]>>>,
+    pub(crate) cancellations: Cancellations,
+    pub(crate) panic_hook: Option<Arc<PanicHook>>,
+    pub(crate) rejection_handler: Option<RejectionHandler>,
+    pub(crate) fallback: Option<ErasedCommandHandler<R>>,
+    pub(crate) fallback_webview_patterns: Vec<Arc<[String]>>,
+    pub(crate) registered_commands: OnceLock<Arc<[String]>>,
 }
 
 impl<R: Runtime> Default for Router<R> {
@@ -20,11 +48,254 @@ impl<R: Runtime> Router<R> {
     pub fn new() -> Self {
         Self {
             commands: HashMap::new(),
+            matcher: matchit::Router::new(),
+            state: Arc::new(StateMap::new()),
+            lenient_args: None,
+            lenient_commands: HashMap::new(),
+            acl: false,
+            max_body_size: None,
+            body_limits: HashMap::new(),
+            timeout: None,
+            timeouts: HashMap::new(),
+            webview_patterns: HashMap::new(),
+            cancellations: Cancellations::default(),
+            panic_hook: None,
+            rejection_handler: None,
+            fallback: None,
+            fallback_webview_patterns: Vec::new(),
+            registered_commands: OnceLock::new(),
         }
     }
 
+    /// Attaches state to the router, which can be extracted in command handlers using [State](crate::State).
+    ///
+    /// Can be called multiple times with different types, attaching the same type
+    /// again replaces the previous value.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::{Router, State};
+    ///
+    /// #[derive(Clone)]
+    /// struct Config {
+    ///     greeting: String,
+    /// }
+    ///
+    /// fn greet(State(config): State<Config>, name: String) -> String {
+    ///    format!("{}, {}!", config.greeting, name)
+    /// }
+    ///
+    /// fn main() {
+    ///     let router: Router<_> = Router::new()
+    ///         .with_state(Config { greeting: "Hello".into() })
+    ///         .command("greet", greet);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn with_state<T: Send + Sync + 'static>(mut self, state: T) -> Self {
+        Arc::make_mut(&mut self.state).insert(TypeId::of::<T>(), Arc::new(state));
+        self
+    }
+
+    /// Configures whether surplus JSON arguments are ignored instead of rejected.
+    ///
+    /// By default, a request with more JSON arguments than the handler takes
+    /// is rejected with [Error::InvalidArgs](crate::Error::InvalidArgs).
+    /// Commands of a nested router keep the setting of that router if it was configured,
+    /// and inherit the setting of this router otherwise.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::Router;
+    ///
+    /// fn greet(name: String) -> String {
+    ///    format!("Hello, {}!", name)
+    /// }
+    ///
+    /// fn main() {
+    ///     // `["Tauri", "ignored"]` is accepted
+    ///     let router: Router<_> = Router::new()
+    ///         .lenient_args(true)
+    ///         .command("greet", greet);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn lenient_args(mut self, lenient: bool) -> Self {
+        self.lenient_args = Some(lenient);
+        self
+    }
+
+    /// Sets the maximum size in bytes of request bodies, unlimited by default.
+    ///
+    /// Larger requests are rejected with [Error::PayloadTooLarge](crate::Error::PayloadTooLarge)
+    /// before reaching the handler. Commands of a nested router keep the limit of that router,
+    /// and [Router::command_max_body_size] overrides it for a single command.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::{Bytes, Router};
+    ///
+    /// fn greet(name: String) -> String {
+    ///    format!("Hello, {}!", name)
+    /// }
+    ///
+    /// fn upload(Bytes(file): Bytes) -> usize {
+    ///    file.len()
+    /// }
+    ///
+    /// fn main() {
+    ///     let router: Router<_> = Router::new()
+    ///         .max_body_size(64 * 1024)
+    ///         .command("greet", greet)
+    ///         .command("upload", upload)
+    ///         .command_max_body_size("upload", 100 * 1024 * 1024);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn max_body_size(mut self, limit: usize) -> Self {
+        self.max_body_size = Some(limit);
+        self
+    }
+
+    /// Sets the maximum size in bytes of request bodies for a single command,
+    /// overriding [Router::max_body_size].
+    pub fn command_max_body_size(mut self, cmd: &str, limit: usize) -> Self {
+        self.body_limits.insert(normalize_command(cmd), limit);
+        self
+    }
+
+    /// Sets the maximum execution time of commands, unlimited by default.
+    ///
+    /// When a command takes longer, its handler future is dropped and the request is rejected
+    /// with [Error::Timeout](crate::Error::Timeout). The deadline is available to handlers
+    /// through the [Deadline](crate::Deadline) extractor. Commands of a nested router keep the timeout
+    /// of that router, and [Router::command_timeout] overrides it for a single command.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use tauri_plugin_router::Router;
+    ///
+    /// async fn greet(name: String) -> String {
+    ///    format!("Hello, {}!", name)
+    /// }
+    ///
+    /// async fn export() -> String {
+    ///    tokio::time::sleep(Duration::from_secs(30)).await;
+    ///    "exported".into()
+    /// }
+    ///
+    /// fn main() {
+    ///     let router: Router<_> = Router::new()
+    ///         .timeout(Duration::from_secs(5))
+    ///         .command("greet", greet)
+    ///         .command("export", export)
+    ///         .command_timeout("export", Duration::from_secs(60));
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum execution time of a single command, overriding [Router::timeout].
+    pub fn command_timeout(mut self, cmd: &str, timeout: Duration) -> Self {
+        self.timeouts.insert(normalize_command(cmd), timeout);
+        self
+    }
+
+    /// Sets a hook called with the command name and the panic message when a command panics,
+    /// to report it to a crash reporter for example.
+    ///
+    /// Panics in commands, including their middleware, are always caught and turned into
+    /// an [Error::Panic](crate::Error::Panic) response. Only the hook of the router
+    /// passed to the plugin is called, hooks of nested routers are ignored.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::Router;
+    ///
+    /// fn divide(a: i32, b: i32) -> i32 {
+    ///    a / b
+    /// }
+    ///
+    /// fn main() {
+    ///     let router: Router<_> = Router::new()
+    ///         .command("divide", divide)
+    ///         .on_panic(|command, message| eprintln!("`{command}` panicked: {message}"));
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn on_panic<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        self.panic_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets a hook converting extractor failures into the response sent to the frontend,
+    /// to localize messages, add context or log them for example.
+    ///
+    /// The [Rejection] carries the command and the position of the failed extractor in the handler arguments.
+    /// Only the hook of the router passed to the plugin is called, hooks of nested routers are ignored.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::{AppError, Rejection, Router};
+    ///
+    /// fn greet(name: String) -> String {
+    ///    format!("Hello, {}!", name)
+    /// }
+    ///
+    /// fn main() {
+    ///     let router: Router<_> = Router::new()
+    ///         .command("greet", greet)
+    ///         .on_rejection(|rejection: Rejection| {
+    ///             eprintln!(
+    ///                 "`{}` rejected argument {:?}: {}",
+    ///                 rejection.command(),
+    ///                 rejection.arg_index(),
+    ///                 rejection.error()
+    ///             );
+    ///             AppError::from(rejection.into_error())
+    ///         });
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn on_rejection<F, Res>(mut self, hook: F) -> Self
+    where
+        F: Fn(Rejection) -> Res + Send + Sync + 'static,
+        Res: IntoResponse,
+    {
+        self.rejection_handler = Some(Arc::new(move |rejection| hook(rejection).into_response()));
+        self
+    }
+
     /// Registers a command handler for the given command name.
     ///
+    /// The command name can contain path parameters such as `documents/:id`,
+    /// which can be extracted in the handler using [Path](crate::Path),
+    /// or a catch-all parameter such as `files/*path`.
+    /// Static command names take precedence over parameterized ones.
+    ///
+    /// Commands only accept `POST` requests, requests with any other method are rejected
+    /// with [Error::MethodNotAllowed](crate::Error::MethodNotAllowed). Use [Router::route]
+    /// to handle other methods.
+    ///
     /// ## Example
     /// ```rust,no_run
     /// use tauri::Runtime;
@@ -41,245 +312,2511 @@ impl<R: Runtime> Router<R> {
     ///         .plugin(tauri_plugin_router::init(router));
     /// }
     /// ```
-    pub fn command<H, T>(mut self, cmd: &str, handler: H) -> Self
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a command with the same name is already registered,
+    /// or if the command name conflicts with another parameterized command.
+    pub fn command<H, T>(self, cmd: &str, handler: H) -> Self
     where
         H: CommandHandler<R, T>,
     {
-        let erased: ErasedCommandHandler<R> =
-            std::sync::Arc::new(move |ctx, req| Box::pin(handler.clone().call(req, ctx)));
-        self.commands.insert(cmd.to_string(), erased);
+        self.route(cmd, crate::post(handler))
+    }
+
+    /// Registers a [MethodRouter] for the given command name, dispatching requests on their HTTP method.
+    ///
+    /// Methods without a handler are rejected with
+    /// [Error::MethodNotAllowed](crate::Error::MethodNotAllowed) and an `Allow` header.
+    /// Methods other than `POST` must also be allowed by the [CorsConfig](crate::CorsConfig).
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::{get, Json, Path, Router};
+    ///
+    /// fn read(Path(id): Path<u32>) -> String {
+    ///    format!("document {id}")
+    /// }
+    ///
+    /// fn write(Path(id): Path<u32>, Json(content): Json<String>) -> bool {
+    ///    true
+    /// }
+    ///
+    /// fn main() {
+    ///     // `<img src="...">` can load `GET` routes directly
+    ///     let router: Router<_> = Router::new()
+    ///         .route("documents/:id", get(read).put(write));
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// Panics under the same conditions as [Router::command].
+    pub fn route(mut self, cmd: &str, method_router: MethodRouter<R>) -> Self {
+        self.insert_command(normalize_command(cmd), method_router.into_handler());
         self
     }
 
-    /// Handles an incoming request by dispatching it to the appropriate command handler.
-    pub(crate) async fn handle_request(
-        &self,
-        app_handle: &tauri::AppHandle<R>,
-        webview_label: &str,
-        request: tauri::http::Request<Vec<u8>>,
-    ) -> tauri::http::Response<Vec<u8>> {
-        // Extract command name from URI path
-        let command_name = request.uri().path().trim_start_matches('/').to_string();
+    /// Sets the handler of requests that don't match any registered command.
+    ///
+    /// The fallback handler supports the same extractors as command handlers,
+    /// the requested command is available through [CommandContext::command], and the
+    /// registered commands through [CommandContext::registered_commands].
+    /// Only the fallback of the router passed to the plugin is used, fallbacks of nested routers are ignored.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri::Runtime;
+    /// use tauri_plugin_router::{AppError, CommandContext, Router};
+    ///
+    /// fn greet(name: String) -> String {
+    ///    format!("Hello, {}!", name)
+    /// }
+    ///
+    /// fn not_found<R: Runtime>(ctx: CommandContext<R>) -> AppError {
+    ///     let suggestions = ctx
+    ///         .registered_commands()
+    ///         .iter()
+    ///         .filter(|cmd| ctx.command().chars().next().is_some_and(|c| cmd.starts_with(c)))
+    ///         .collect::<Vec<_>>();
+    ///     AppError::new("COMMAND_NOT_FOUND", format!("unknown command `{}`", ctx.command()))
+    ///         .with_status(tauri::http::StatusCode::NOT_FOUND)
+    ///         .with_details(suggestions)
+    /// }
+    ///
+    /// fn main() {
+    ///     let router: Router<_> = Router::new()
+    ///         .command("greet", greet)
+    ///         .fallback(not_found);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn fallback<H, T>(mut self, handler: H) -> Self
+    where
+        H: CommandHandler<R, T>,
+    {
+        self.fallback = Some(std::sync::Arc::new(move |ctx, req| {
+            Box::pin(handler.clone().call(req, ctx))
+        }));
+        self
+    }
 
-        // Create CommandContext from Tauri context with request ownership
-        let ctx = CommandContext {
-            app_handle: app_handle.clone(),
-            webview_label: webview_label.to_string(),
-            json_args: None,
-        };
+    /// Registers a command handler that accepts its JSON arguments by name.
+    ///
+    /// The request body can be a JSON object, whose keys are matched against `arg_names`
+    /// in the order of the handler's JSON arguments, or a JSON array of positional arguments.
+    /// Extractors that don't read JSON arguments, such as [AppHandle](crate::AppHandle), are not named.
+    /// Like [Router::command], it only accepts `POST` requests.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::{AppHandle, Router};
+    ///
+    /// fn greet<R: tauri::Runtime>(_app: AppHandle<R>, name: String, greeting: String) -> String {
+    ///    format!("{}, {}!", greeting, name)
+    /// }
+    ///
+    /// fn main() {
+    ///     // invoked with `{ "greeting": "Hello", "name": "Tauri" }`
+    ///     let router: Router<_> = Router::new()
+    ///         .command_with_arg_names("greet", &["name", "greeting"], greet);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// Panics under the same conditions as [Router::command].
+    pub fn command_with_arg_names<H, T>(self, cmd: &str, arg_names: &[&str], handler: H) -> Self
+    where
+        H: CommandHandler<R, T>,
+    {
+        let arg_names: Arc<[String]> = arg_names.iter().map(ToString::to_string).collect();
+        let erased: ErasedCommandHandler<R> = std::sync::Arc::new(move |mut ctx, req| {
+            ctx.arg_names = Some(Arc::clone(&arg_names));
+            Box::pin(handler.clone().call(req, ctx))
+        });
+        self.route(
+            cmd,
+            MethodRouter::new().on_erased(tauri::http::Method::POST, erased),
+        )
+    }
 
-        // Find and execute the command handler
-        match self.commands.get(&command_name) {
-            Some(handler) => handler(ctx, request).await,
-            None => {
-                let error = crate::Error::CommandNotFound(command_name);
-                crate::response::error(error)
+    /// Nests all the commands of another router under the given prefix.
+    ///
+    /// A command `list` in the nested router is invoked as `{prefix}/list`.
+    /// Middleware added to the nested router only applies to its own commands.
+    ///
+    /// State attached to the nested router is merged into this router,
+    /// state of the same type already attached to this router takes precedence.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::Router;
+    ///
+    /// fn list() -> Vec<String> {
+    ///    vec!["a.txt".into(), "b.txt".into()]
+    /// }
+    ///
+    /// fn main() {
+    ///     let files: Router<_> = Router::new().command("list", list);
+    ///
+    ///     // invoked as `files/list`
+    ///     let router = Router::new().nest("files", files);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a nested command conflicts with an already registered command.
+    pub fn nest(mut self, prefix: &str, router: Router<R>) -> Self {
+        let prefix = normalize_command(prefix);
+        self.merge_state(&router.state);
+        for (old_cmd, handler) in router.commands {
+            let cmd = match (prefix.is_empty(), old_cmd.is_empty()) {
+                (true, _) => old_cmd.clone(),
+                (false, true) => prefix.clone(),
+                (false, false) => format!("{prefix}/{old_cmd}"),
+            };
+            if let Some(limit) = router
+                .body_limits
+                .get(&old_cmd)
+                .copied()
+                .or(router.max_body_size)
+            {
+                self.body_limits.insert(cmd.clone(), limit);
+            }
+            if let Some(timeout) = router.timeouts.get(&old_cmd).copied().or(router.timeout) {
+                self.timeouts.insert(cmd.clone(), timeout);
             }
+            if let Some(patterns) = router.webview_patterns.get(&old_cmd) {
+                self.webview_patterns.insert(cmd.clone(), patterns.clone());
+            }
+            if let Some(lenient) = router
+                .lenient_commands
+                .get(&old_cmd)
+                .copied()
+                .or(router.lenient_args)
+            {
+                self.lenient_commands.insert(cmd.clone(), lenient);
+            }
+            self.insert_command(cmd, handler);
         }
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::*;
+    /// Merges all the commands of another router into this router.
+    ///
+    /// State is merged the same way as in [Router::nest].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a merged command conflicts with an already registered command.
+    pub fn merge(self, router: Router<R>) -> Self {
+        self.nest("", router)
+    }
 
-    // Test utilities
-    // --------------
+    fn insert_command(&mut self, cmd: String, handler: ErasedCommandHandler<R>) {
+        if self.commands.contains_key(&cmd) {
+            panic!("conflicting command: `{cmd}` is already registered");
+        }
+        if let Err(e) = self.matcher.insert(format!("/{cmd}"), cmd.clone()) {
+            panic!("conflicting command: `{cmd}`: {e}");
+        }
+        self.commands.insert(cmd, handler);
+        self.registered_commands = OnceLock::new();
+    }
 
-    macro_rules! call_raw {
-        ($router:expr, $app:expr, $command:expr, $body:expr) => {{
-            $router
+    fn merge_state(&mut self, state: &StateMap) {
+        if state.is_empty() {
+            return;
+        }
+
+        let own = Arc::make_mut(&mut self.state);
+        for (type_id, value) in state {
+            own.entry(*type_id).or_insert_with(|| Arc::clone(value));
+        }
+    }
+
+    /// Wraps all commands registered so far with the given [Middleware].
+    ///
+    /// Commands registered after this call are not affected, which allows applying
+    /// a middleware to only a subset of commands. When multiple middleware are added,
+    /// the last one added runs first.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri::Runtime;
+    /// use tauri_plugin_router::{CommandContext, Next, Router};
+    ///
+    /// async fn log<R: Runtime>(
+    ///     ctx: CommandContext<R>,
+    ///     req: tauri::http::Request<Vec<u8>>,
+    ///     next: Next<R>,
+    /// ) -> tauri::http::Response<Vec<u8>> {
+    ///     println!("{} called {}", ctx.webview_label(), req.uri().path());
+    ///     next.run(ctx, req).await
+    /// }
+    ///
+    /// fn greet(name: String) -> String {
+    ///    format!("Hello, {}!", name)
+    /// }
+    ///
+    /// fn main() {
+    ///     let router: Router<_> = Router::new()
+    ///         .command("greet", greet)
+    ///         .layer(log);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn layer<M: Middleware<R>>(mut self, middleware: M) -> Self {
+        let middleware = Arc::new(middleware);
+        for handler in self.commands.values_mut() {
+            let middleware = Arc::clone(&middleware);
+            let next = Arc::clone(handler);
+            *handler = Arc::new(move |ctx, req| {
+                let next = Next {
+                    handler: Arc::clone(&next),
+                };
+                middleware.call(ctx, req, next)
+            });
+        }
+        self
+    }
+
+    /// Restricts all commands registered so far, and the fallback if already set,
+    /// to the webviews whose label matches one of the given patterns.
+    ///
+    /// Patterns can contain `*` to match any sequence of characters and `?` to match a single character.
+    /// Calls from other webviews are rejected with [Error::Forbidden](crate::Error::Forbidden)
+    /// before reaching any middleware or the handler. When called multiple times,
+    /// the webview must match the patterns of every call. To restrict a single command, or a group of commands,
+    /// register them on their own router and [nest](Router::nest) or [merge](Router::merge) it.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// use tauri_plugin_router::Router;
+    ///
+    /// fn reset() {}
+    ///
+    /// fn greet(name: String) -> String {
+    ///    format!("Hello, {}!", name)
+    /// }
+    ///
+    /// fn main() {
+    ///     let admin: Router<_> = Router::new()
+    ///         .command("reset", reset)
+    ///         .allow_webviews(["settings", "settings-*"]);
+    ///
+    ///     let router = Router::new()
+    ///         .command("greet", greet)
+    ///         .nest("admin", admin);
+    ///
+    ///     let app = tauri::Builder::default()
+    ///         .plugin(tauri_plugin_router::init(router));
+    /// }
+    /// ```
+    pub fn allow_webviews<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let patterns: Arc<[String]> = patterns.into_iter().map(Into::into).collect();
+        for cmd in self.commands.keys() {
+            self.webview_patterns
+                .entry(cmd.clone())
+                .or_default()
+                .push(Arc::clone(&patterns));
+        }
+        if self.fallback.is_some() {
+            self.fallback_webview_patterns.push(patterns);
+        }
+        self
+    }
+
+    /// Wraps all commands registered so far with the given [tower_layer::Layer].
+    ///
+    /// The [CommandContext] of the request is available in the request extensions.
+    /// Like [Router::layer], commands registered after this call are not affected.
+    #[cfg(feature = "tower")]
+    pub fn tower_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower_layer::Layer<crate::HandlerService<R>>,
+        L::Service: tower_service::Service<
+                tauri::http::Request<Vec<u8>>,
+                Response = tauri::http::Response<Vec<u8>>,
+            > + Clone
+            + Send
+            + Sync
+            + 'static,
+        <L::Service as tower_service::Service<tauri::http::Request<Vec<u8>>>>::Future: Send,
+        <L::Service as tower_service::Service<tauri::http::Request<Vec<u8>>>>::Error:
+            std::fmt::Display + Send,
+    {
+        use tower_service::Service;
+
+        for handler in self.commands.values_mut() {
+            let service = layer.layer(crate::HandlerService {
+                handler: Arc::clone(handler),
+            });
+            *handler = Arc::new(move |ctx, mut req| {
+                let mut service = service.clone();
+                req.extensions_mut().insert(ctx);
+                Box::pin(async move {
+                    let result = match std::future::poll_fn(|cx| service.poll_ready(cx)).await {
+                        Ok(()) => service.call(req).await,
+                        Err(e) => Err(e),
+                    };
+
+                    result.unwrap_or_else(|e| {
+                        crate::response::error(crate::Error::MiddlewareError(e.to_string()))
+                    })
+                })
+            });
+        }
+        self
+    }
+
+    /// Handles an incoming request by dispatching it to the appropriate command handler.
+    pub(crate) async fn handle_request(
+        &self,
+        app_handle: &tauri::AppHandle<R>,
+        webview_label: &str,
+        request: tauri::http::Request<Vec<u8>>,
+    ) -> tauri::http::Response<Vec<u8>> {
+        let (command_name, query) = command_from_uri(request.uri());
+
+        if command_name == CANCEL_COMMAND {
+            let request_id = String::from_utf8_lossy(request.body());
+            self.cancellations.cancel(webview_label, request_id.trim());
+            return tauri::http::Response::new(Vec::new());
+        }
+
+        // Find the command handler and capture its path parameters,
+        // unknown commands are dispatched to the fallback handler if any
+        let route = format!("/{command_name}");
+        let (handler, command, path_params) = match self.matcher.at(&route) {
+            Ok(matched) => {
+                let path_params = matched
+                    .params
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                (
+                    &self.commands[matched.value],
+                    matched.value.as_str(),
+                    path_params,
+                )
+            }
+            Err(_) => match &self.fallback {
+                Some(fallback) => (fallback, command_name.as_str(), Vec::new()),
+                None => {
+                    let error = crate::Error::CommandNotFound(command_name);
+                    return crate::response::error(error);
+                }
+            },
+        };
+
+        let is_fallback = !self.commands.contains_key(command);
+        let patterns = match is_fallback {
+            false => self.webview_patterns.get(command).map(Vec::as_slice),
+            true => Some(self.fallback_webview_patterns.as_slice()),
+        };
+        if let Some(error) = patterns.and_then(|patterns| check_webview(patterns, webview_label)) {
+            return crate::response::error(error);
+        }
+
+        let limit = self
+            .body_limits
+            .get(command)
+            .copied()
+            .or(self.max_body_size);
+        if let Some(limit) = limit.filter(|limit| request.body().len() > *limit) {
+            let error = crate::Error::PayloadTooLarge(format!(
+                "request body of {} bytes exceeds the limit of {limit} bytes",
+                request.body().len()
+            ));
+            return crate::response::error(error);
+        }
+
+        if self.acl {
+            let permission = match is_fallback {
+                false => command,
+                true => crate::acl::FALLBACK_COMMAND,
+            };
+            if let Err(error) = crate::acl::check_access(app_handle, webview_label, permission) {
+                return crate::response::error(error);
+            }
+        }
+
+        let timeout = self.timeouts.get(command).copied().or(self.timeout);
+
+        // Requests with an id can be aborted by the frontend through the cancel command
+        let request_id = request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|id| id.to_str().ok());
+        let (cancellation_token, _guard) = match request_id {
+            Some(request_id) => {
+                let (token, guard) = self.cancellations.register(webview_label, request_id);
+                (token, Some(guard))
+            }
+            None => (tokio_util::sync::CancellationToken::new(), None),
+        };
+
+        // Create CommandContext from Tauri context with request ownership
+        let ctx = CommandContext {
+            app_handle: app_handle.clone(),
+            webview_label: webview_label.to_string(),
+            json_args: None,
+            arg_names: None,
+            arg_index: 0,
+            lenient_args: self
+                .lenient_commands
+                .get(command)
+                .copied()
+                .or(self.lenient_args)
+                .unwrap_or(false),
+            state: Arc::clone(&self.state),
+            path_params,
+            query,
+            deadline: timeout.map(|timeout| std::time::Instant::now() + timeout),
+            cancellation_token: cancellation_token.clone(),
+            command: command.to_string(),
+            rejection_handler: self.rejection_handler.clone(),
+            registered_commands: Arc::clone(self.registered_commands.get_or_init(|| {
+                let mut commands = self.commands.keys().cloned().collect::<Vec<_>>();
+                commands.sort();
+                commands.into()
+            })),
+        };
+
+        // Ranged responses are read after the command returns, from the range requested by the client
+        let range_headers = crate::range::RangeHeaders::new(request.method(), request.headers());
+        // Streamed responses are forwarded to the channel passed by the client, if any
+        let stream_target = crate::stream::StreamTarget::new(app_handle, webview_label, &request);
+
+        let response = async {
+            let response = CatchUnwind(Box::pin(async move {
+                let response = handler(ctx, request).await;
+                let response = range_headers.resolve(response).await;
+                stream_target.resolve(response).await
+            }));
+            match cancellation_token.run_until_cancelled(response).await {
+                Some(Ok(response)) => response,
+                Some(Err(payload)) => {
+                    let message = panic_message(payload.as_ref());
+                    if let Some(hook) = &self.panic_hook {
+                        hook(command, &message);
+                    }
+                    crate::response::error(crate::Error::Panic(message))
+                }
+                None => crate::response::error(crate::Error::Cancelled(format!(
+                    "command `{}` was aborted",
+                    command
+                ))),
+            }
+        };
+
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, response)
+                .await
+                .unwrap_or_else(|_| {
+                    // stop the work spawned by the command along with its future
+                    cancellation_token.cancel();
+                    crate::response::error(crate::Error::Timeout(format!(
+                        "command `{}` did not complete within {timeout:?}",
+                        command
+                    )))
+                }),
+            None => response.await,
+        }
+    }
+}
+
+/// Trims leading and trailing slashes from a command name or prefix.
+fn normalize_command(cmd: &str) -> String {
+    cmd.trim_matches('/').to_string()
+}
+
+/// Extracts the command name and the query string from the URI of a request.
+///
+/// Commands are matched on the raw path, so that an encoded `/` or `?` stays part of
+/// the path parameter capturing it, which is only decoded by the [Path](crate::Path) extractor.
+pub(crate) fn command_from_uri(uri: &tauri::http::Uri) -> (String, Option<String>) {
+    let path = uri
+        .path()
+        .split('/')
+        .map(decode_segment)
+        .collect::<Vec<_>>()
+        .join("/");
+    (
+        normalize_command(&path),
+        uri.query().map(ToString::to_string),
+    )
+}
+
+/// Percent-decodes a path segment to match command names with non-ASCII characters,
+/// keeping the `%`, `/` and `?` characters encoded.
+fn decode_segment(segment: &str) -> String {
+    percent_encoding::percent_decode_str(segment)
+        .decode_utf8_lossy()
+        .replace('%', "%25")
+        .replace('/', "%2F")
+        .replace('?', "%3F")
+}
+
+/// Returns an error if the webview doesn't match every set of [Router::allow_webviews] patterns.
+fn check_webview(patterns: &[Arc<[String]>], label: &str) -> Option<crate::Error> {
+    let allowed = patterns
+        .iter()
+        .all(|patterns| patterns.iter().any(|pattern| matches_glob(pattern, label)));
+    (!allowed).then(|| {
+        crate::Error::Forbidden(format!(
+            "webview `{label}` is not allowed to call this command"
+        ))
+    })
+}
+
+/// Matches a text against a glob pattern, where `*` matches any sequence of characters
+/// and `?` matches a single character.
+pub(crate) fn matches_glob(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    // position of the last `*` in the pattern, and of the text it was matched against
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    // Test utilities
+    // --------------
+
+    macro_rules! call_raw {
+        ($router:expr, $app:expr, $command:expr, $body:expr) => {{
+            call_raw!($router, $app, "POST", $command, $body)
+        }};
+        ($router:expr, $app:expr, $method:expr, $command:expr, $body:expr) => {{
+            $router
+                .handle_request(
+                    $app.handle(),
+                    "test_webview",
+                    tauri::http::Request::builder()
+                        .method($method)
+                        .uri(format!("router://localhost/{}", $command))
+                        .body($body)
+                        .unwrap(),
+                )
+                .await
+        }};
+    }
+
+    macro_rules! call_json {
+        ($router:expr, $app:expr, $command:expr, $args:expr) => {{
+            let body = serde_json::to_vec($args).unwrap();
+            call_raw!($router, $app, $command, body)
+        }};
+    }
+
+    macro_rules! body_as_string {
+        ($response:expr) => {
+            String::from_utf8($response.into_body()).unwrap()
+        };
+    }
+
+    macro_rules! body_as_json {
+        ($response:expr) => {
+            serde_json::from_slice(&$response.into_body()).unwrap()
+        };
+    }
+
+    // Sample command handlers for testing
+    // -----------------------------------
+
+    fn greet(name: String) -> String {
+        format!("Hello, {}!", name)
+    }
+
+    fn add(a: u32, b: u32) -> u32 {
+        a + b
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize)]
+    enum Operation {
+        Add,
+        Subtract,
+        Multiply,
+        Divide,
+    }
+
+    fn calc(a: f64, b: f64, operation: Operation) -> std::result::Result<f64, String> {
+        match operation {
+            Operation::Add => Ok(a + b),
+            Operation::Subtract => Ok(a - b),
+            Operation::Multiply => Ok(a * b),
+            Operation::Divide => {
+                if b == 0.0 {
+                    Err("Division by zero".to_string())
+                } else {
+                    Ok(a / b)
+                }
+            }
+        }
+    }
+
+    fn no_args() -> String {
+        "Hello from no args!".to_string()
+    }
+
+    fn unit_return(_x: u32) {}
+
+    fn with_app<R: Runtime>(_app: AppHandle<R>, name: String) -> String {
+        format!("With App, {}!", name)
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize, Clone)]
+    struct Message {
+        content: String,
+    }
+
+    fn raw_to_json(Bytes(bytes): Bytes) -> Message {
+        Message {
+            content: format!("Raw to json, received {} bytes", bytes.len()),
+        }
+    }
+
+    fn json_to_raw(data: Message) -> Bytes {
+        Bytes(serde_json::to_vec(&data).unwrap())
+    }
+
+    fn request_response(Request(req): Request) -> Response {
+        Response(
+            tauri::http::Response::builder()
+                .header("Content-Type", "application/octet-stream")
+                .body(req.into_body())
+                .unwrap(),
+        )
+    }
+
+    // Async handler examples for testing
+    async fn async_greet(name: String) -> String {
+        format!("Hello async, {}!", name)
+    }
+
+    async fn async_add(a: u32, b: u32) -> u32 {
+        // Simulate some async work (without external dependencies)
+        a + b
+    }
+
+    async fn async_with_result(value: i32) -> std::result::Result<i32, String> {
+        if value < 0 {
+            Err("Negative value not allowed".to_string())
+        } else {
+            Ok(value * 2)
+        }
+    }
+
+    async fn async_with_app<R: Runtime>(_app: AppHandle<R>, name: String) -> String {
+        format!("Async with App, {}!", name)
+    }
+
+    #[tokio::test]
+    async fn it_works() {
+        let app = tauri::test::mock_app();
+
+        let router = Router::new()
+            .command("greet", greet)
+            .command("add", add)
+            .command("calc", calc)
+            .command("no_args", no_args)
+            .command("unit_return", unit_return)
+            .command("with_app", with_app)
+            .command("raw_to_json", raw_to_json)
+            .command("json_to_raw", json_to_raw)
+            .command("request_response", request_response)
+            .command("async_greet", async_greet)
+            .command("async_add", async_add)
+            .command("async_with_result", async_with_result)
+            .command("async_with_app", async_with_app);
+
+        // test sync handlers
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "add", &[3, 5]);
+        assert_eq!(body_as_string!(response), "8");
+
+        let response = call_json!(router, app, "calc", &(10.0, 2.0, Operation::Multiply));
+        assert_eq!(body_as_string!(response), "20.0");
+
+        let response = call_json!(router, app, "calc", &(1.0, 0.0, Operation::Divide));
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(body_as_string!(response), "\"Division by zero\"");
+
+        let response = call_json!(router, app, "no_args", &());
+        assert_eq!(body_as_string!(response), "\"Hello from no args!\"");
+
+        let response = call_json!(router, app, "unit_return", &[42]);
+        assert_eq!(body_as_string!(response), "null");
+
+        let response = call_json!(router, app, "with_app", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"With App, Tauri!\"");
+
+        let response = call_raw!(router, app, "raw_to_json", b"hello world".to_vec());
+        let msg: Message = body_as_json!(response);
+        assert_eq!(msg.content, "Raw to json, received 11 bytes");
+
+        let message = Message {
+            content: "Hello, JSON!".to_string(),
+        };
+        let message_clone = message.clone();
+        let response = call_json!(router, app, "json_to_raw", &[message_clone]);
+        assert_eq!(response.into_body(), serde_json::to_vec(&message).unwrap());
+
+        let response = call_raw!(router, app, "request_response", b"echo this".to_vec());
+        assert_eq!(response.into_body(), b"echo this".to_vec());
+
+        // test async handlers
+        let response = call_json!(router, app, "async_greet", &["World"]);
+        assert_eq!(body_as_string!(response), "\"Hello async, World!\"");
+
+        let response = call_json!(router, app, "async_add", &[7, 3]);
+        assert_eq!(body_as_string!(response), "10");
+
+        let response = call_json!(router, app, "async_with_result", &[5]);
+        assert_eq!(body_as_string!(response), "10");
+
+        let response = call_json!(router, app, "async_with_result", &[-5]);
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(body_as_string!(response), "\"Negative value not allowed\"");
+
+        let response = call_json!(router, app, "async_with_app", &["Async"]);
+        assert_eq!(body_as_string!(response), "\"Async with App, Async!\"");
+    }
+
+    async fn deny_guest<R: Runtime>(
+        ctx: CommandContext<R>,
+        req: tauri::http::Request<Vec<u8>>,
+        next: Next<R>,
+    ) -> tauri::http::Response<Vec<u8>> {
+        if req.headers().contains_key("x-guest") {
+            return tauri::http::Response::builder()
+                .status(tauri::http::StatusCode::UNAUTHORIZED)
+                .body(Vec::new())
+                .unwrap();
+        }
+
+        let mut response = next.run(ctx, req).await;
+        response.headers_mut().insert(
+            "x-layer",
+            tauri::http::HeaderValue::from_static("deny_guest"),
+        );
+        response
+    }
+
+    async fn webview_label<R: Runtime>(
+        ctx: CommandContext<R>,
+        req: tauri::http::Request<Vec<u8>>,
+        next: Next<R>,
+    ) -> tauri::http::Response<Vec<u8>> {
+        let label = ctx.webview_label().to_string();
+        let mut response = next.run(ctx, req).await;
+        response
+            .headers_mut()
+            .insert("x-webview", label.parse().unwrap());
+        response
+    }
+
+    #[tokio::test]
+    async fn middleware() {
+        let app = tauri::test::mock_app();
+
+        let router = Router::new()
+            .command("greet", greet)
+            .layer(deny_guest)
+            .command("add", add)
+            .layer(webview_label);
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(response.headers()["x-layer"], "deny_guest");
+        assert_eq!(response.headers()["x-webview"], "test_webview");
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        // commands registered after a layer are not wrapped by it
+        let response = call_json!(router, app, "add", &[1, 2]);
+        assert!(!response.headers().contains_key("x-layer"));
+        assert_eq!(response.headers()["x-webview"], "test_webview");
+        assert_eq!(body_as_string!(response), "3");
+
+        let response = router
+            .handle_request(
+                app.handle(),
+                "test_webview",
+                tauri::http::Request::builder()
+                    .method("POST")
+                    .uri("router://localhost/greet")
+                    .header("x-guest", "1")
+                    .body(serde_json::to_vec(&["Tauri"]).unwrap())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), tauri::http::StatusCode::UNAUTHORIZED);
+        assert!(response.into_body().is_empty());
+    }
+
+    #[cfg(feature = "tower")]
+    #[tokio::test]
+    async fn tower_layer() {
+        #[derive(Clone)]
+        struct WebviewHeader<S>(S);
+
+        impl<S> tower_service::Service<tauri::http::Request<Vec<u8>>> for WebviewHeader<S>
+        where
+            S: tower_service::Service<
+                    tauri::http::Request<Vec<u8>>,
+                    Response = tauri::http::Response<Vec<u8>>,
+                > + Send
+                + 'static,
+            S::Future: Send,
+        {
+            type Response = S::Response;
+            type Error = S::Error;
+            type Future = std::pin::Pin<
+                Box<
+                    dyn std::future::Future<Output = std::result::Result<S::Response, S::Error>>
+                        + Send,
+                >,
+            >;
+
+            fn poll_ready(
+                &mut self,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<std::result::Result<(), Self::Error>> {
+                self.0.poll_ready(cx)
+            }
+
+            fn call(&mut self, req: tauri::http::Request<Vec<u8>>) -> Self::Future {
+                let label = req
+                    .extensions()
+                    .get::<CommandContext<tauri::test::MockRuntime>>()
+                    .map(|ctx| ctx.webview_label().to_string())
+                    .unwrap_or_default();
+                let fut = self.0.call(req);
+                Box::pin(async move {
+                    let mut response = fut.await?;
+                    response
+                        .headers_mut()
+                        .insert("x-webview", label.parse().unwrap());
+                    Ok(response)
+                })
+            }
+        }
+
+        struct WebviewHeaderLayer;
+
+        impl<S> tower_layer::Layer<S> for WebviewHeaderLayer {
+            type Service = WebviewHeader<S>;
+
+            fn layer(&self, inner: S) -> Self::Service {
+                WebviewHeader(inner)
+            }
+        }
+
+        let app = tauri::test::mock_app();
+        let router = Router::new()
+            .command("greet", greet)
+            .tower_layer(WebviewHeaderLayer);
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(response.headers()["x-webview"], "test_webview");
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+    }
+
+    #[tokio::test]
+    async fn nest_and_merge() {
+        let app = tauri::test::mock_app();
+
+        let math = Router::new()
+            .command("add", add)
+            .layer(webview_label)
+            .with_state(Counter(7));
+        let greetings = Router::new()
+            .command("greet", greet)
+            .command("async_greet", async_greet);
+
+        let router = Router::new()
+            .command("no_args", no_args)
+            .nest("/math/", math)
+            .merge(greetings);
+
+        let response = call_json!(router, app, "math/add", &[1, 2]);
+        assert_eq!(response.headers()["x-webview"], "test_webview");
+        assert_eq!(body_as_string!(response), "3");
+
+        // an encoded slash doesn't separate nested commands
+        let response = call_json!(router, app, "math%2Fadd", &[2, 2]);
+        assert_eq!(response.status(), tauri::http::StatusCode::NOT_FOUND);
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert!(!response.headers().contains_key("x-webview"));
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "add", &[1, 2]);
+        assert_eq!(response.status(), tauri::http::StatusCode::NOT_FOUND);
+
+        assert!(router.state.contains_key(&TypeId::of::<Counter>()));
+    }
+
+    #[test]
+    #[should_panic(expected = "conflicting command: `math/add`")]
+    fn nest_conflict() {
+        let _: Router<tauri::test::MockRuntime> = Router::new()
+            .command("math/add", add)
+            .nest("math", Router::new().command("add", add));
+    }
+
+    #[test]
+    #[should_panic(expected = "conflicting command: `greet`")]
+    fn merge_conflict() {
+        let _: Router<tauri::test::MockRuntime> = Router::new()
+            .command("greet", greet)
+            .merge(Router::new().command("greet", async_greet));
+    }
+
+    #[derive(serde::Deserialize)]
+    struct PageParams {
+        id: u32,
+        page: u32,
+    }
+
+    fn document(Path(id): Path<u32>) -> String {
+        format!("document {id}")
+    }
+
+    fn document_page(Path((id, page)): Path<(u32, u32)>, title: String) -> String {
+        format!("document {id}, page {page}: {title}")
+    }
+
+    fn document_page_struct(Path(params): Path<PageParams>) -> String {
+        format!("document {}, page {}", params.id, params.page)
+    }
+
+    fn document_latest() -> String {
+        "latest document".to_string()
+    }
+
+    fn file(Path(path): Path<String>) -> String {
+        path
+    }
+
+    #[tokio::test]
+    async fn path_params() {
+        let app = tauri::test::mock_app();
+
+        let documents = Router::new()
+            .command(":id", document)
+            .command("latest", document_latest)
+            .command(":id/pages/:page", document_page);
+
+        let router = Router::new()
+            .nest("documents", documents)
+            .command("v2/documents/:id/pages/:page", document_page_struct)
+            .command("files/*path", file)
+            .command("notes/:title", file)
+            .command("notes/:title/tags", |Path(title): Path<String>| {
+                format!("tags of {title}")
+            });
+
+        let response = call_json!(router, app, "documents/42", &());
+        assert_eq!(body_as_string!(response), "\"document 42\"");
+
+        // static routes take precedence
+        let response = call_json!(router, app, "documents/latest", &());
+        assert_eq!(body_as_string!(response), "\"latest document\"");
+
+        let response = call_json!(router, app, "documents/42/pages/3", &["Intro"]);
+        assert_eq!(body_as_string!(response), "\"document 42, page 3: Intro\"");
+
+        let response = call_json!(router, app, "v2/documents/42/pages/3", &());
+        assert_eq!(body_as_string!(response), "\"document 42, page 3\"");
+
+        let response = call_json!(router, app, "files/a/b.txt", &());
+        assert_eq!(body_as_string!(response), "\"a/b.txt\"");
+
+        // parameters are decoded after matching, encoded `/` and `?` stay in their segment
+        let response = call_json!(router, app, "notes/a%2Fb%3Fc%25", &());
+        assert_eq!(body_as_string!(response), "\"a/b?c%\"");
+
+        let response = call_json!(router, app, "notes/%C3%A9t%C3%A9/tags", &());
+        assert_eq!(body_as_string!(response), "\"tags of été\"");
+
+        let response = call_json!(router, app, "documents/abc/pages/3", &["Intro"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "DeserializationError");
+    }
+
+    #[tokio::test]
+    async fn named_args() {
+        let app = tauri::test::mock_app();
+
+        let router = Router::new()
+            .command_with_arg_names("calc", &["a", "b", "operation"], calc)
+            .command_with_arg_names("with_app", &["name"], with_app)
+            .command("add", add);
+
+        let response = call_json!(
+            router,
+            app,
+            "calc",
+            &serde_json::json!({ "operation": "Subtract", "b": 2.0, "a": 10.0 })
+        );
+        assert_eq!(body_as_string!(response), "8.0");
+
+        // positional arguments are still accepted
+        let response = call_json!(router, app, "calc", &(10.0, 2.0, Operation::Add));
+        assert_eq!(body_as_string!(response), "12.0");
+
+        let response = call_json!(
+            router,
+            app,
+            "with_app",
+            &serde_json::json!({ "name": "Tauri" })
+        );
+        assert_eq!(body_as_string!(response), "\"With App, Tauri!\"");
+
+        let response = call_json!(
+            router,
+            app,
+            "calc",
+            &serde_json::json!({ "a": 10.0, "operation": "Add" })
+        );
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "InvalidArgs");
+        assert_eq!(error["message"], "missing argument `b`");
+
+        let response = call_json!(
+            router,
+            app,
+            "calc",
+            &serde_json::json!({ "a": 10.0, "c": 2.0, "operation": "Add" })
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "InvalidArgs");
+        assert_eq!(error["message"], "unexpected argument `c`");
+
+        let response = call_json!(
+            router,
+            app,
+            "calc",
+            &serde_json::json!({ "a": "ten", "b": 2.0, "operation": "Add" })
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "DeserializationError");
+        assert!(error["message"]
+            .as_str()
+            .unwrap()
+            .contains("for argument `a`"));
+
+        let response = call_json!(router, app, "add", &serde_json::json!({ "a": 1, "b": 2 }));
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "InvalidArgs");
+    }
+
+    fn greet_optional(
+        name: String,
+        title: Option<String>,
+        OrDefault(times): OrDefault<u32>,
+    ) -> String {
+        match title {
+            Some(title) => format!("{title} {name} x{times}"),
+            None => format!("{name} x{times}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn optional_args() {
+        let app = tauri::test::mock_app();
+
+        let router = Router::new()
+            .command("optional_args", greet_optional)
+            .command_with_arg_names(
+                "named_optional_args",
+                &["name", "title", "times"],
+                greet_optional,
+            );
+
+        let response = call_json!(router, app, "optional_args", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Tauri x0\"");
+
+        let response = call_json!(router, app, "optional_args", &("Tauri", "Dr."));
+        assert_eq!(body_as_string!(response), "\"Dr. Tauri x0\"");
+
+        let response = call_json!(router, app, "optional_args", &("Tauri", (), 3));
+        assert_eq!(body_as_string!(response), "\"Tauri x3\"");
+
+        let response = call_json!(
+            router,
+            app,
+            "named_optional_args",
+            &serde_json::json!({ "name": "Tauri", "times": 2 })
+        );
+        assert_eq!(body_as_string!(response), "\"Tauri x2\"");
+
+        // required arguments are still required
+        let response = call_json!(router, app, "optional_args", &[(); 0]);
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["message"], "no more arguments available");
+
+        let response = call_json!(
+            router,
+            app,
+            "named_optional_args",
+            &serde_json::json!({ "times": 2 })
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["message"], "missing argument `name`");
+    }
+
+    #[tokio::test]
+    async fn surplus_args() {
+        let app = tauri::test::mock_app();
+
+        let lenient = Router::new().lenient_args(true).command("greet", greet);
+        let router = Router::new()
+            .command("greet", greet)
+            .command_with_arg_names("named_greet", &["name"], greet)
+            .nest("lenient", lenient);
+
+        let response = call_json!(router, app, "greet", &("Tauri", "extra", 1));
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(
+            error["message"],
+            "too many arguments: expected 1, received 3"
+        );
+
+        let response = call_json!(router, app, "lenient/greet", &("Tauri", "extra"));
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "named_greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "named_greet", &["Tauri", "extra"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+
+        // handlers without JSON arguments reject them too
+        let router = Router::new()
+            .command("ping", || "pong")
+            .command("documents/:id", document)
+            .nest(
+                "lenient",
+                Router::new().lenient_args(true).command("ping", || "pong"),
+            );
+
+        let response = call_json!(router, app, "ping", &["extra"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(
+            error["message"],
+            "too many arguments: expected 0, received 1"
+        );
+
+        let response = call_json!(router, app, "ping", &[(); 0]);
+        assert_eq!(body_as_string!(response), "\"pong\"");
+
+        let response = call_raw!(router, app, "ping", Vec::new());
+        assert_eq!(body_as_string!(response), "\"pong\"");
+
+        let response = call_json!(router, app, "documents/1", &["extra"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(
+            error["message"],
+            "too many arguments: expected 0, received 1"
+        );
+
+        let response = call_json!(router, app, "documents/1", &[(); 0]);
+        assert_eq!(body_as_string!(response), "\"document 1\"");
+
+        let response = call_json!(router, app, "lenient/ping", &["extra"]);
+        assert_eq!(body_as_string!(response), "\"pong\"");
+
+        // nested routers inherit the setting unless they configure it
+        let router = Router::new()
+            .lenient_args(true)
+            .nest("inherited", Router::new().command("ping", || "pong"))
+            .nest(
+                "strict",
+                Router::new().lenient_args(false).command("ping", || "pong"),
+            );
+
+        let response = call_json!(router, app, "inherited/ping", &["extra"]);
+        assert_eq!(body_as_string!(response), "\"pong\"");
+
+        let response = call_json!(router, app, "strict/ping", &["extra"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+    }
+
+    fn json_document(Json(mut message): Json<Message>) -> Json<Message> {
+        message.content = message.content.to_uppercase();
+        Json(message)
+    }
+
+    #[tokio::test]
+    async fn json() {
+        let app = tauri::test::mock_app();
+
+        let router = Router::new().command("json_document", json_document);
+
+        let request = |content_type: &str| {
+            tauri::http::Request::builder()
+                .method("POST")
+                .uri("router://localhost/json_document")
+                .header("Content-Type", content_type)
+                .body(br#"{"content":"hello"}"#.to_vec())
+                .unwrap()
+        };
+
+        let response = router
+            .handle_request(app.handle(), "test_webview", request("application/json"))
+            .await;
+        assert_eq!(response.headers()["Content-Type"], "application/json");
+        assert_eq!(body_as_string!(response), r#"{"content":"HELLO"}"#);
+
+        let response = router
+            .handle_request(
+                app.handle(),
+                "test_webview",
+                request("application/vnd.api+json; charset=utf-8"),
+            )
+            .await;
+        assert_eq!(body_as_string!(response), r#"{"content":"HELLO"}"#);
+
+        let response = router
+            .handle_request(app.handle(), "test_webview", request("text/plain"))
+            .await;
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "UnsupportedMediaType");
+    }
+
+    #[derive(serde::Deserialize)]
+    struct SearchParams {
+        q: String,
+        limit: Option<u32>,
+    }
+
+    fn search(Query(params): Query<SearchParams>) -> String {
+        format!("{} ({})", params.q, params.limit.unwrap_or(10))
+    }
+
+    #[tokio::test]
+    async fn query() {
+        let app = tauri::test::mock_app();
+
+        let router = Router::new().command("search", search);
+
+        let response = call_json!(router, app, "search?q=tauri&limit=5", &());
+        assert_eq!(body_as_string!(response), "\"tauri (5)\"");
+
+        let response = call_json!(router, app, "search?q=hello%20world", &());
+        assert_eq!(body_as_string!(response), "\"hello world (10)\"");
+
+        // an encoded `?` doesn't start the query string
+        let response = call_json!(router, app, "search%3Fq%3Dtauri", &());
+        assert_eq!(response.status(), tauri::http::StatusCode::NOT_FOUND);
+
+        let response = call_json!(router, app, "search", &());
+        assert_eq!(response.status(), tauri::http::StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "DeserializationError");
+    }
+
+    #[test]
+    #[should_panic(expected = "conflicting command: `documents/:name`")]
+    fn path_params_conflict() {
+        let _: Router<tauri::test::MockRuntime> = Router::new()
+            .command("documents/:id", document)
+            .command("documents/:name", document);
+    }
+
+    #[derive(Clone)]
+    struct Counter(u32);
+
+    #[derive(Clone)]
+    struct Prefix(&'static str);
+
+    fn with_state(State(counter): State<Counter>, State(prefix): State<Prefix>) -> String {
+        format!("{}{}", prefix.0, counter.0)
+    }
+
+    #[tokio::test]
+    async fn state() {
+        let app = tauri::test::mock_app();
+        tauri::Manager::manage(&app, Counter(1));
+        tauri::Manager::manage(&app, Prefix("managed: "));
+
+        let router = Router::new()
+            .with_state(Counter(42))
+            .command("with_state", with_state);
+
+        // router state takes precedence over tauri managed state
+        let response = call_json!(router, app, "with_state", &());
+        assert_eq!(body_as_string!(response), "\"managed: 42\"");
+
+        let router = Router::new().command("with_state", with_state);
+        let app = tauri::test::mock_app();
+
+        let response = call_json!(router, app, "with_state", &());
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "StateNotFound");
+    }
+
+    #[tokio::test]
+    async fn cors() {
+        use tauri::http::{header::*, Method, Request, StatusCode};
+
+        let app = tauri::test::mock_app();
+        let router = Router::new().command("greet", greet);
+        let cors = CorsConfig::new()
+            .allow_origin("tauri://localhost")
+            .allow_headers(["content-type"])
+            .allow_methods([Method::POST])
+            .max_age(std::time::Duration::from_secs(600));
+
+        let request = |method: Method, origin: Option<&str>| {
+            let mut request = Request::builder()
+                .method(method)
+                .uri("router://localhost/greet");
+            if let Some(origin) = origin {
+                request = request.header(ORIGIN, origin);
+            }
+            request
+                .body(serde_json::to_vec(&["Tauri"]).unwrap())
+                .unwrap()
+        };
+
+        let response = crate::handle_protocol_request(
+            &router,
+            &cors,
+            app.handle(),
+            "test_webview",
+            request(Method::OPTIONS, Some("tauri://localhost")),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "tauri://localhost"
+        );
+        // the headers of the frontend client are always allowed
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_HEADERS],
+            "content-type, x-router-request-id, x-router-channel, x-router-progress-channel"
+        );
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_METHODS], "POST");
+        assert_eq!(response.headers()[ACCESS_CONTROL_MAX_AGE], "600");
+
+        let response = crate::handle_protocol_request(
+            &router,
+            &cors,
+            app.handle(),
+            "test_webview",
+            request(Method::POST, Some("tauri://localhost")),
+        )
+        .await;
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "tauri://localhost"
+        );
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = crate::handle_protocol_request(
+            &router,
+            &cors,
+            app.handle(),
+            "test_webview",
+            request(Method::GET, Some("tauri://localhost")),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        for origin in [Some("https://example.com"), None] {
+            let response = crate::handle_protocol_request(
+                &router,
+                &cors,
+                app.handle(),
+                "test_webview",
+                request(Method::POST, origin),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            let error: serde_json::Value = body_as_json!(response);
+            assert_eq!(error["type"], "Forbidden");
+        }
+
+        // media elements don't send an `Origin` header without the `crossorigin` attribute
+        let router = router.route(
+            "thumbnails/:id",
+            get(|Path(id): Path<u8>| Binary::new(vec![id; 4])),
+        );
+        let cors = cors
+            .allow_methods([Method::GET, Method::HEAD, Method::POST])
+            .allow_missing_origin(["thumbnails/*"]);
+        let media_request = |method: Method, command: &str| {
+            Request::builder()
+                .method(method)
+                .uri(format!("router://localhost/{command}"))
+                .body(Vec::new())
+                .unwrap()
+        };
+
+        let response = crate::handle_protocol_request(
+            &router,
+            &cors,
+            app.handle(),
+            "test_webview",
+            media_request(Method::GET, "thumbnails/7"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response
+            .headers()
+            .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+        assert_eq!(response.into_body(), vec![7; 4]);
+
+        for (method, command) in [(Method::POST, "thumbnails/7"), (Method::GET, "greet")] {
+            let response = crate::handle_protocol_request(
+                &router,
+                &cors,
+                app.handle(),
+                "test_webview",
+                media_request(method, command),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        // the default configuration allows any origin
+        let response = crate::handle_protocol_request(
+            &router,
+            &CorsConfig::default(),
+            app.handle(),
+            "test_webview",
+            request(Method::POST, Some("https://example.com")),
+        )
+        .await;
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+    }
+
+    async fn cached<R: Runtime>(
+        _ctx: CommandContext<R>,
+        _req: tauri::http::Request<Vec<u8>>,
+        _next: Next<R>,
+    ) -> tauri::http::Response<Vec<u8>> {
+        tauri::http::Response::new(b"\"cached\"".to_vec())
+    }
+
+    #[tokio::test]
+    async fn allow_webviews() {
+        let app = tauri::test::mock_app();
+
+        let admin = Router::new()
+            .command("greet", greet)
+            .allow_webviews(["settings", "test_*"]);
+        let restricted = Router::new()
+            .command("greet", greet)
+            .allow_webviews(["settings"]);
+        let router = Router::new()
+            .nest("admin", admin)
+            .nest("restricted", restricted);
+
+        // `call_json!` calls from the `test_webview` webview
+        let response = call_json!(router, app, "admin/greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "restricted/greet", &["Tauri"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::FORBIDDEN);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "Forbidden");
+
+        // layers added later don't run before the check
+        let router = Router::new()
+            .command("greet", greet)
+            .fallback(|| "fallback")
+            .allow_webviews(["settings"])
+            .layer(cached);
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::FORBIDDEN);
+
+        let response = call_json!(router, app, "unknown", &[(); 0]);
+        assert_eq!(response.status(), tauri::http::StatusCode::FORBIDDEN);
+
+        let router = Router::new()
+            .command("greet", greet)
+            .allow_webviews(["test_*"])
+            .nest(
+                "restricted",
+                Router::new()
+                    .command("greet", greet)
+                    .allow_webviews(["settings"]),
+            )
+            .allow_webviews(["*_webview"]);
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "restricted/greet", &["Tauri"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::FORBIDDEN);
+
+        assert!(matches_glob("settings-?", "settings-1"));
+        assert!(matches_glob("*-window-*", "main-window-2"));
+        assert!(!matches_glob("settings-?", "settings-12"));
+        assert!(!matches_glob("settings", "settings-1"));
+    }
+
+    /// Creates a mock app with a `test_webview` webview, granted the given router commands
+    /// by an app permission.
+    fn mock_app_with_commands(commands: &[&str]) -> tauri::App<tauri::test::MockRuntime> {
+        use std::collections::BTreeMap;
+        use tauri::utils::acl::{
+            capability::Capability, manifest::Manifest, resolved::Resolved, Permission, APP_ACL_KEY,
+        };
+
+        let permission: Permission = serde_json::from_value(serde_json::json!({
+            "identifier": "allow-router",
+            "commands": {
+                "allow": commands
+                    .iter()
+                    .map(|cmd| format!("plugin:router|{cmd}"))
+                    .collect::<Vec<_>>(),
+            },
+        }))
+        .unwrap();
+        let capability: Capability = serde_json::from_value(serde_json::json!({
+            "identifier": "default",
+            "windows": ["test_webview"],
+            "permissions": ["allow-router"],
+        }))
+        .unwrap();
+
+        let manifest = Manifest {
+            permissions: BTreeMap::from([(permission.identifier.clone(), permission)]),
+            ..Default::default()
+        };
+        let acl = BTreeMap::from([(APP_ACL_KEY.to_string(), manifest)]);
+        let capabilities = BTreeMap::from([(capability.identifier.clone(), capability)]);
+        let resolved = Resolved::resolve(
+            &acl,
+            capabilities,
+            tauri::utils::platform::Target::current(),
+        )
+        .unwrap();
+
+        let mut context = tauri::test::mock_context(tauri::test::noop_assets());
+        *context.runtime_authority_mut() = tauri::ipc::RuntimeAuthority::new(acl, resolved);
+        let app = tauri::test::mock_builder().build(context).unwrap();
+        tauri::WebviewWindowBuilder::new(&app, "test_webview", Default::default())
+            .build()
+            .unwrap();
+        app
+    }
+
+    #[tokio::test]
+    async fn acl() {
+        let app = tauri::test::mock_app();
+        let mut router = Router::new().command("greet", greet);
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        // the mock app has no `test_webview` webview to resolve the capabilities of
+        router.acl = true;
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::NOT_FOUND);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "WebviewNotFound");
+
+        let app = mock_app_with_commands(&["greet", "files/:id", "a/b"]);
+        let mut router = Router::new()
+            .command("greet", greet)
+            .command("secret", greet)
+            .command("files/:id", document)
+            .command("files/id", || "static")
+            .command("a/b", || "a/b")
+            .command("a_b", || "a_b")
+            .fallback(|| "fallback");
+        router.acl = true;
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "files/1", &[(); 0]);
+        assert_eq!(body_as_string!(response), "\"document 1\"");
+
+        let response = call_json!(router, app, "a/b", &[(); 0]);
+        assert_eq!(body_as_string!(response), "\"a/b\"");
+
+        // similar routes don't share a permission
+        for command in ["secret", "files/id", "a_b"] {
+            let response = call_json!(router, app, command, &[(); 0]);
+            assert_eq!(response.status(), tauri::http::StatusCode::FORBIDDEN);
+            let error: serde_json::Value = body_as_json!(response);
+            assert_eq!(error["type"], "Forbidden");
+        }
+
+        let response = call_json!(router, app, "unknown", &[(); 0]);
+        assert_eq!(response.status(), tauri::http::StatusCode::FORBIDDEN);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(
+            error["message"],
+            "`plugin:router|/fallback` is not allowed for webview `test_webview`"
+        );
+
+        let app = mock_app_with_commands(&["/fallback"]);
+        let response = call_json!(router, app, "unknown", &[(); 0]);
+        assert_eq!(body_as_string!(response), "\"fallback\"");
+
+        // permissions must spell the route pattern exactly, mis-spelled routes fail closed
+        let app = mock_app_with_commands(&["files/1", "files/:identifier", "/files/:id", "Greet"]);
+        for command in ["files/1", "greet"] {
+            let response = call_json!(router, app, command, &["Tauri"]);
+            assert_eq!(response.status(), tauri::http::StatusCode::FORBIDDEN);
+        }
+    }
+
+    fn body_len(Bytes(body): Bytes) -> usize {
+        body.len()
+    }
+
+    #[tokio::test]
+    async fn max_body_size() {
+        let app = tauri::test::mock_app();
+
+        let nested = Router::new().max_body_size(1024).command("greet", greet);
+        let router = Router::new()
+            .max_body_size(16)
+            .command("greet", greet)
+            .command("echo", body_len)
+            .command_max_body_size("echo", 64)
+            .nest("nested", nested);
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "greet", &["Tauri, but with a longer name"]);
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::PAYLOAD_TOO_LARGE
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "PayloadTooLarge");
+        assert_eq!(
+            error["message"],
+            "request body of 33 bytes exceeds the limit of 16 bytes"
+        );
+
+        let response = call_raw!(router, app, "echo", vec![0; 64]);
+        assert_eq!(response.status(), tauri::http::StatusCode::OK);
+        let response = call_raw!(router, app, "echo", vec![0; 65]);
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::PAYLOAD_TOO_LARGE
+        );
+
+        let response = call_json!(
+            router,
+            app,
+            "nested/greet",
+            &["Tauri, but with a longer name"]
+        );
+        assert_eq!(response.status(), tauri::http::StatusCode::OK);
+    }
+
+    async fn slow(deadline: Deadline) -> Option<u128> {
+        let remaining = deadline.remaining();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        remaining.map(|remaining| remaining.as_millis())
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let app = tauri::test::mock_app();
+
+        let aborted = Arc::new(AtomicBool::new(false));
+        let router = Router::new()
+            .with_state(Arc::clone(&aborted))
+            .timeout(Duration::from_millis(50))
+            .command("slow", slow)
+            .command("wait", wait_for_abort)
+            .command("greet", greet)
+            .command("patient", slow)
+            .command_timeout("patient", Duration::from_secs(10));
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "slow", &[(); 0]);
+        assert_eq!(response.status(), tauri::http::StatusCode::GATEWAY_TIMEOUT);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "Timeout");
+
+        let response = call_json!(router, app, "patient", &[(); 0]);
+        assert_eq!(response.status(), tauri::http::StatusCode::OK);
+        let remaining: u128 = body_as_json!(response);
+        assert!(remaining > 9_000 && remaining <= 10_000);
+
+        // the cancellation token of a timed out command is cancelled
+        let response = call_json!(router, app, "wait", &[(); 0]);
+        assert_eq!(response.status(), tauri::http::StatusCode::GATEWAY_TIMEOUT);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(aborted.load(Ordering::SeqCst));
+    }
+
+    async fn wait_for_abort(token: CancellationToken, State(aborted): State<Arc<AtomicBool>>) {
+        tokio::spawn(async move {
+            token.cancelled().await;
+            aborted.store(true, Ordering::SeqCst);
+        });
+        std::future::pending::<()>().await
+    }
+
+    #[tokio::test]
+    async fn cancellation() {
+        let app = tauri::test::mock_app();
+
+        let aborted = Arc::new(AtomicBool::new(false));
+        let router = Router::new()
+            .with_state(Arc::clone(&aborted))
+            .command("wait", wait_for_abort);
+
+        let wait = router.handle_request(
+            app.handle(),
+            "test_webview",
+            tauri::http::Request::builder()
+                .method("POST")
+                .uri("router://localhost/wait")
+                .header("X-Router-Request-Id", "1")
+                .body(b"[]".to_vec())
+                .unwrap(),
+        );
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            // requests of other webviews can't be aborted
+            router
                 .handle_request(
-                    $app.handle(),
-                    "test_webview",
+                    app.handle(),
+                    "other_webview",
                     tauri::http::Request::builder()
-                        .uri(format!("router://localhost/{}", $command))
-                        .body($body)
+                        .method("POST")
+                        .uri("router://localhost/__router__/cancel")
+                        .body(b"1".to_vec())
                         .unwrap(),
                 )
-                .await
-        }};
-    }
+                .await;
+            call_raw!(router, app, "__router__/cancel", b"1".to_vec())
+        };
 
-    macro_rules! call_json {
-        ($router:expr, $app:expr, $command:expr, $args:expr) => {{
-            let body = serde_json::to_vec($args).unwrap();
-            call_raw!($router, $app, $command, body)
-        }};
-    }
+        let (response, cancel_response) = tokio::join!(wait, cancel);
+        assert_eq!(cancel_response.status(), tauri::http::StatusCode::OK);
+        assert_eq!(response.status().as_u16(), 499);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "Cancelled");
 
-    macro_rules! body_as_string {
-        ($response:expr) => {
-            String::from_utf8($response.into_body()).unwrap()
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(aborted.load(Ordering::SeqCst));
+        assert!(router.cancellations.tokens.lock().unwrap().is_empty());
+
+        // a cancel that arrives before its request aborts it once it arrives
+        let response = call_raw!(router, app, "__router__/cancel", b"2".to_vec());
+        assert_eq!(response.status(), tauri::http::StatusCode::OK);
+
+        let request = || {
+            tauri::http::Request::builder()
+                .method("POST")
+                .uri("router://localhost/wait")
+                .header("X-Router-Request-Id", "2")
+                .body(b"[]".to_vec())
+                .unwrap()
         };
+        let response = tokio::time::timeout(
+            Duration::from_secs(1),
+            router.handle_request(app.handle(), "test_webview", request()),
+        )
+        .await
+        .expect("the request was not aborted");
+        assert_eq!(response.status().as_u16(), 499);
+        let key = ("test_webview".to_string(), "2".to_string());
+        assert!(!router
+            .cancellations
+            .early
+            .lock()
+            .unwrap()
+            .contains_key(&key));
+        assert!(router.cancellations.tokens.lock().unwrap().is_empty());
     }
 
-    macro_rules! body_as_json {
-        ($response:expr) => {
-            serde_json::from_slice(&$response.into_body()).unwrap()
-        };
+    fn divide(a: i32, b: i32) -> i32 {
+        a / b
     }
 
-    // Sample command handlers for testing
-    // -----------------------------------
+    #[tokio::test]
+    async fn panic() {
+        let app = tauri::test::mock_app();
 
-    fn greet(name: String) -> String {
-        format!("Hello, {}!", name)
-    }
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let router = Router::new().command("divide", divide).on_panic({
+            let reported = Arc::clone(&reported);
+            move |command, message| {
+                reported
+                    .lock()
+                    .unwrap()
+                    .push(format!("{command}: {message}"))
+            }
+        });
 
-    fn add(a: u32, b: u32) -> u32 {
-        a + b
-    }
+        let response = call_json!(router, app, "divide", &(6, 3));
+        assert_eq!(body_as_string!(response), "2");
 
-    #[derive(serde::Deserialize, serde::Serialize)]
-    enum Operation {
-        Add,
-        Subtract,
-        Multiply,
-        Divide,
+        let response = call_json!(router, app, "divide", &(1, 0));
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "Panic");
+        assert_eq!(error["message"], "attempt to divide by zero");
+        assert_eq!(
+            *reported.lock().unwrap(),
+            ["divide: attempt to divide by zero"]
+        );
     }
 
-    fn calc(a: f64, b: f64, operation: Operation) -> std::result::Result<f64, String> {
-        match operation {
-            Operation::Add => Ok(a + b),
-            Operation::Subtract => Ok(a - b),
-            Operation::Multiply => Ok(a * b),
-            Operation::Divide => {
-                if b == 0.0 {
-                    Err("Division by zero".to_string())
-                } else {
-                    Ok(a / b)
-                }
-            }
+    fn find_document(id: u32) -> std::result::Result<String, AppError> {
+        match id {
+            1 => Ok("Notes".into()),
+            _ => Err(AppError::new("DOCUMENT_NOT_FOUND", "no such document")
+                .with_status(tauri::http::StatusCode::NOT_FOUND)
+                .with_details(serde_json::json!({ "id": id }))),
         }
     }
 
-    fn no_args() -> String {
-        "Hello from no args!".to_string()
-    }
+    #[tokio::test]
+    async fn app_error() {
+        let app = tauri::test::mock_app();
 
-    fn unit_return(_x: u32) {}
+        let router = Router::new()
+            .command("find_document", find_document)
+            .command("fail", || {
+                AppError::new("ALWAYS_FAILS", "this command always fails")
+            });
 
-    fn with_app<R: Runtime>(_app: AppHandle<R>, name: String) -> String {
-        format!("With App, {}!", name)
-    }
+        let response = call_json!(router, app, "find_document", &[1]);
+        assert_eq!(body_as_string!(response), "\"Notes\"");
 
-    #[derive(serde::Deserialize, serde::Serialize, Clone)]
-    struct Message {
-        content: String,
-    }
+        let response = call_json!(router, app, "find_document", &[2]);
+        assert_eq!(response.status(), tauri::http::StatusCode::NOT_FOUND);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(
+            error,
+            serde_json::json!({
+                "type": "DOCUMENT_NOT_FOUND",
+                "message": "no such document",
+                "details": { "id": 2 }
+            })
+        );
 
-    fn raw_to_json(Bytes(bytes): Bytes) -> Message {
-        Message {
-            content: format!("Raw to json, received {} bytes", bytes.len()),
-        }
+        let response = call_json!(router, app, "fail", &[(); 0]);
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "ALWAYS_FAILS");
+
+        let error = AppError::from(Error::Forbidden("not allowed".into()));
+        assert_eq!(error.code(), "Forbidden");
+        assert_eq!(error.message(), "not allowed");
+        assert_eq!(error.status(), tauri::http::StatusCode::FORBIDDEN);
+
+        // errors of the crate are sent with their own status code
+        let router = Router::new()
+            .command("forbidden", || -> crate::Result<String> {
+                Err(Error::Forbidden("not allowed".into()))
+            })
+            .command("errors", || {
+                vec![AppError::new("A", "a").with_status(tauri::http::StatusCode::NOT_FOUND)]
+            });
+
+        let response = call_json!(router, app, "forbidden", &[(); 0]);
+        assert_eq!(response.status(), tauri::http::StatusCode::FORBIDDEN);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(
+            error,
+            serde_json::json!({ "type": "Forbidden", "message": "not allowed" })
+        );
+
+        // nested errors are serialized as is
+        let response = call_json!(router, app, "errors", &[(); 0]);
+        assert_eq!(response.status(), tauri::http::StatusCode::OK);
+        let errors: serde_json::Value = body_as_json!(response);
+        assert_eq!(errors, serde_json::json!([{ "type": "A", "message": "a" }]));
     }
 
-    fn json_to_raw(data: Message) -> Bytes {
-        Bytes(serde_json::to_vec(&data).unwrap())
+    #[tokio::test]
+    async fn on_rejection() {
+        let app = tauri::test::mock_app();
+
+        let router = Router::new()
+            .command("greet", greet)
+            .command("add", |_app: AppHandle<_>, a: i32, b: i32| a + b)
+            .on_rejection(|rejection: Rejection| {
+                AppError::new("REJECTED", rejection.error().to_string())
+                    .with_status(tauri::http::StatusCode::UNPROCESSABLE_ENTITY)
+                    .with_details(serde_json::json!({
+                        "command": rejection.command(),
+                        "argIndex": rejection.arg_index(),
+                    }))
+            });
+
+        let response = call_json!(router, app, "add", &serde_json::json!([1, "two"]));
+        assert_eq!(
+            response.status(),
+            tauri::http::StatusCode::UNPROCESSABLE_ENTITY
+        );
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "REJECTED");
+        assert_eq!(
+            error["details"],
+            serde_json::json!({ "command": "add", "argIndex": 2 })
+        );
+
+        let response = call_json!(router, app, "greet", &["Tauri", "extra"]);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(
+            error["details"],
+            serde_json::json!({ "command": "greet", "argIndex": null })
+        );
     }
 
-    fn request_response(Request(req): Request) -> Response {
-        Response(
-            tauri::http::Response::builder()
-                .header("Content-Type", "application/octet-stream")
-                .body(req.into_body())
-                .unwrap(),
+    fn did_you_mean<R: Runtime>(ctx: CommandContext<R>, name: Option<String>) -> AppError {
+        let suggestions = ctx
+            .registered_commands()
+            .iter()
+            .filter(|cmd| {
+                ctx.command()
+                    .chars()
+                    .next()
+                    .is_some_and(|c| cmd.starts_with(c))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        AppError::new(
+            "UNKNOWN_COMMAND",
+            format!("unknown command `{}`", ctx.command()),
         )
+        .with_status(tauri::http::StatusCode::NOT_FOUND)
+        .with_details(serde_json::json!({ "suggestions": suggestions, "name": name }))
     }
 
-    // Async handler examples for testing
-    async fn async_greet(name: String) -> String {
-        format!("Hello async, {}!", name)
-    }
+    #[tokio::test]
+    async fn fallback() {
+        let app = tauri::test::mock_app();
 
-    async fn async_add(a: u32, b: u32) -> u32 {
-        // Simulate some async work (without external dependencies)
-        a + b
+        let router = Router::new()
+            .command("greet", greet)
+            .command("greet_twice", greet)
+            .command("sum", |a: i32, b: i32| a + b);
+
+        let response = call_json!(router, app, "gret", &["Tauri"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::NOT_FOUND);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "CommandNotFound");
+
+        let router = router.fallback(did_you_mean);
+
+        let response = call_json!(router, app, "greet", &["Tauri"]);
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+
+        let response = call_json!(router, app, "gret", &["Tauri"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::NOT_FOUND);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "UNKNOWN_COMMAND");
+        assert_eq!(
+            error["details"],
+            serde_json::json!({ "suggestions": ["greet", "greet_twice"], "name": "Tauri" })
+        );
+
+        // the first character of the command may be multibyte
+        let response = call_json!(router, app, "%C3%A9clair", &["Tauri"]);
+        assert_eq!(response.status(), tauri::http::StatusCode::NOT_FOUND);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(
+            error["details"],
+            serde_json::json!({ "suggestions": [], "name": "Tauri" })
+        );
     }
 
-    async fn async_with_result(value: i32) -> std::result::Result<i32, String> {
-        if value < 0 {
-            Err("Negative value not allowed".to_string())
-        } else {
-            Ok(value * 2)
+    #[tokio::test]
+    async fn method_routing() {
+        use tauri::http::{header::ALLOW, Method as HttpMethod, Request, StatusCode};
+
+        let app = tauri::test::mock_app();
+
+        let router = Router::new()
+            .route(
+                "documents/:id",
+                get(|Path(id): Path<u32>| format!("document {id}"))
+                    .put(|Path(id): Path<u32>, content: String| format!("{id}: {content}"))
+                    .delete(|Method(method): Method| method.to_string()),
+            )
+            .command("greet", greet)
+            .command_with_arg_names("named_greet", &["name"], greet);
+
+        let request = |method: HttpMethod, command: &str, body: Vec<u8>| {
+            Request::builder()
+                .method(method)
+                .uri(format!("router://localhost/{command}"))
+                .body(body)
+                .unwrap()
+        };
+        macro_rules! call {
+            ($method:expr, $command:expr, $body:expr) => {
+                router
+                    .handle_request(
+                        app.handle(),
+                        "test_webview",
+                        request($method, $command, $body),
+                    )
+                    .await
+            };
         }
+
+        let response = call!(HttpMethod::GET, "documents/1", Vec::new());
+        assert_eq!(body_as_string!(response), "\"document 1\"");
+
+        let response = call!(
+            HttpMethod::PUT,
+            "documents/1",
+            serde_json::to_vec(&["hello"]).unwrap()
+        );
+        assert_eq!(body_as_string!(response), "\"1: hello\"");
+
+        let response = call!(HttpMethod::DELETE, "documents/1", Vec::new());
+        assert_eq!(body_as_string!(response), "\"DELETE\"");
+
+        // `HEAD` falls back to the `GET` handler without a body
+        let response = call!(HttpMethod::HEAD, "documents/1", Vec::new());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.body().is_empty());
+
+        let response = call!(
+            HttpMethod::POST,
+            "documents/1",
+            serde_json::to_vec(&["hello"]).unwrap()
+        );
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "GET, PUT, DELETE, HEAD");
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "MethodNotAllowed");
+
+        // commands only accept `POST`
+        let response = call!(
+            HttpMethod::PATCH,
+            "greet",
+            serde_json::to_vec(&["Tauri"]).unwrap()
+        );
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "POST");
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "MethodNotAllowed");
+
+        let response = call!(HttpMethod::GET, "greet", Vec::new());
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let response = call!(HttpMethod::GET, "named_greet", Vec::new());
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "POST");
+
+        let response = call!(
+            HttpMethod::POST,
+            "greet",
+            serde_json::to_vec(&["Tauri"]).unwrap()
+        );
+        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
     }
 
-    async fn async_with_app<R: Runtime>(_app: AppHandle<R>, name: String) -> String {
-        format!("Async with App, {}!", name)
+    #[tokio::test]
+    async fn binary_response() {
+        use tauri::http::header::*;
+
+        let app = tauri::test::mock_app();
+
+        let router = Router::new()
+            .route(
+                "thumbnails/:id",
+                get(|Path(id): Path<u8>| Binary::new(vec![id; 4]).content_type("image/png")),
+            )
+            .route(
+                "exports/:name",
+                get(|Path(name): Path<String>| {
+                    Binary::new(b"a,b".to_vec())
+                        .content_type("text/csv")
+                        .attachment(name)
+                }),
+            );
+
+        let response = call_raw!(router, app, "GET", "thumbnails/7", Vec::new());
+        assert_eq!(response.headers()[CONTENT_TYPE], "image/png");
+        assert_eq!(response.headers()[CONTENT_LENGTH], "4");
+        assert!(response.headers().get(CONTENT_DISPOSITION).is_none());
+        assert_eq!(response.into_body(), vec![7; 4]);
+
+        let response = call_raw!(router, app, "GET", "exports/report.csv", Vec::new());
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/csv");
+        assert_eq!(
+            response.headers()[CONTENT_DISPOSITION],
+            "attachment; filename=\"report.csv\""
+        );
+        assert_eq!(body_as_string!(response), "a,b");
+
+        let response = call_raw!(
+            router,
+            app,
+            "GET",
+            "exports/r%C3%A9sum%C3%A9.csv",
+            Vec::new()
+        );
+        assert_eq!(
+            response.headers()[CONTENT_DISPOSITION],
+            "attachment; filename=\"r_sum_.csv\"; filename*=UTF-8''r%C3%A9sum%C3%A9%2Ecsv"
+        );
     }
 
     #[tokio::test]
-    async fn it_works() {
+    async fn range_requests() {
+        use tauri::http::{header::*, Method as HttpMethod, Request, StatusCode};
+
         let app = tauri::test::mock_app();
 
+        let path = std::env::temp_dir().join("tauri-plugin-router-range.bin");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let file = path.clone();
+        let large = path.clone();
         let router = Router::new()
-            .command("greet", greet)
-            .command("add", add)
-            .command("calc", calc)
-            .command("no_args", no_args)
-            .command("unit_return", unit_return)
-            .command("with_app", with_app)
-            .command("raw_to_json", raw_to_json)
-            .command("json_to_raw", json_to_raw)
-            .command("request_response", request_response)
-            .command("async_greet", async_greet)
-            .command("async_add", async_add)
-            .command("async_with_result", async_with_result)
-            .command("async_with_app", async_with_app);
+            .route(
+                "file",
+                get(move || FileResponse::new(file.clone()).chunk_size(4)),
+            )
+            .route("bytes", get(|| RangedBytes::new(b"abcdef".to_vec())))
+            .route(
+                "dir/*name",
+                get(|Path(name): Path<String>| FileResponse::from_dir(std::env::temp_dir(), name)),
+            )
+            .route(
+                "large",
+                get(move || FileResponse::new(large.clone()).chunk_size(4).max_size(8)),
+            )
+            .route("missing", get(|| FileResponse::new("/does/not/exist.bin")));
 
-        // test sync handlers
-        let response = call_json!(router, app, "greet", &["Tauri"]);
-        assert_eq!(body_as_string!(response), "\"Hello, Tauri!\"");
+        let request = |method: HttpMethod, command: &str, headers: &[(HeaderName, &str)]| {
+            let mut request = Request::builder()
+                .method(method)
+                .uri(format!("router://localhost/{command}"));
+            for (name, value) in headers {
+                request = request.header(name, *value);
+            }
+            request.body(Vec::new()).unwrap()
+        };
+        macro_rules! call {
+            ($method:expr, $command:expr, $headers:expr) => {
+                router
+                    .handle_request(
+                        app.handle(),
+                        "test_webview",
+                        request($method, $command, $headers),
+                    )
+                    .await
+            };
+        }
 
-        let response = call_json!(router, app, "add", &[3, 5]);
-        assert_eq!(body_as_string!(response), "8");
+        let response = call!(HttpMethod::GET, "file", &[]);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[ACCEPT_RANGES], "bytes");
+        assert_eq!(response.headers()[CONTENT_LENGTH], "10");
+        let etag = response.headers()[ETAG].to_str().unwrap().to_string();
+        assert_eq!(body_as_string!(response), "0123456789");
 
-        let response = call_json!(router, app, "calc", &(10.0, 2.0, Operation::Multiply));
-        assert_eq!(body_as_string!(response), "{\"Ok\":20.0}"); // TODO: fix Result serialization
+        let response = call!(HttpMethod::GET, "file", &[(RANGE, "bytes=2-4")]);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[CONTENT_RANGE], "bytes 2-4/10");
+        assert_eq!(body_as_string!(response), "234");
 
-        let response = call_json!(router, app, "no_args", &());
-        assert_eq!(body_as_string!(response), "\"Hello from no args!\"");
+        // open-ended ranges are truncated to the chunk size
+        let response = call!(HttpMethod::GET, "file", &[(RANGE, "bytes=5-")]);
+        assert_eq!(response.headers()[CONTENT_RANGE], "bytes 5-8/10");
+        assert_eq!(body_as_string!(response), "5678");
 
-        let response = call_json!(router, app, "unit_return", &[42]);
-        assert_eq!(body_as_string!(response), "null");
+        let response = call!(
+            HttpMethod::GET,
+            "file",
+            &[(RANGE, "bytes=-2"), (IF_RANGE, etag.as_str())]
+        );
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body_as_string!(response), "89");
 
-        let response = call_json!(router, app, "with_app", &["Tauri"]);
-        assert_eq!(body_as_string!(response), "\"With App, Tauri!\"");
+        // a stale validator receives the whole file
+        let response = call!(
+            HttpMethod::GET,
+            "file",
+            &[(RANGE, "bytes=-2"), (IF_RANGE, "\"stale\"")]
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_as_string!(response), "0123456789");
 
-        let response = call_raw!(router, app, "raw_to_json", b"hello world".to_vec());
-        let msg: Message = body_as_json!(response);
-        assert_eq!(msg.content, "Raw to json, received 11 bytes");
+        let response = call!(HttpMethod::GET, "file", &[(RANGE, "bytes=20-")]);
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(response.headers()[CONTENT_RANGE], "bytes */10");
 
-        let message = Message {
-            content: "Hello, JSON!".to_string(),
+        // paths inside a directory can't escape it
+        let response = call!(HttpMethod::GET, "dir/tauri-plugin-router-range.bin", &[]);
+        assert_eq!(body_as_string!(response), "0123456789");
+
+        for command in [
+            "dir/../tauri-plugin-router-range.bin",
+            "dir/%2Fetc%2Fpasswd",
+            "dir/a/..%2F..%2Fetc%2Fpasswd",
+        ] {
+            let response = call!(HttpMethod::GET, command, &[]);
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        // whole files are only read up to the maximum size
+        let response = call!(HttpMethod::GET, "large", &[]);
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "PayloadTooLarge");
+
+        let response = call!(HttpMethod::GET, "large", &[(RANGE, "bytes=0-")]);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body_as_string!(response), "0123");
+
+        let response = call!(HttpMethod::HEAD, "file", &[(RANGE, "bytes=0-1")]);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[CONTENT_LENGTH], "2");
+        assert!(response.body().is_empty());
+
+        let response = call!(HttpMethod::GET, "bytes", &[(RANGE, "bytes=1-2")]);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[CONTENT_RANGE], "bytes 1-2/6");
+        assert_eq!(body_as_string!(response), "bc");
+
+        let response = call!(HttpMethod::GET, "missing", &[]);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "FileNotFound");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn stream_body() {
+        use tauri::http::{header::CONTENT_TYPE, Request, StatusCode};
+
+        let app = tauri::test::mock_app();
+
+        let router = Router::new().command("export", || {
+            let (tx, rx) = tokio::sync::mpsc::channel(4);
+            tokio::spawn(async move {
+                for i in 0..3 {
+                    tx.send(format!("row {i}\n")).await.unwrap();
+                }
+            });
+            StreamBody::from_receiver(rx).content_type("text/plain")
+        });
+
+        // without a channel, the chunks are collected into the body
+        let response = call_raw!(router, app, "export", Vec::new());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/plain");
+        assert_eq!(body_as_string!(response), "row 0\nrow 1\nrow 2\n");
+
+        let request = |channel: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("router://localhost/export")
+                .header("X-Router-Channel", channel)
+                .body(Vec::new())
+                .unwrap()
         };
-        let message_clone = message.clone();
-        let response = call_json!(router, app, "json_to_raw", &[message_clone]);
-        assert_eq!(response.into_body(), serde_json::to_vec(&message).unwrap());
 
-        let response = call_raw!(router, app, "request_response", b"echo this".to_vec());
-        assert_eq!(response.into_body(), b"echo this".to_vec());
+        let response = router
+            .handle_request(app.handle(), "test_webview", request("not a channel"))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "InvalidArgs");
 
-        // test async handlers
-        let response = call_json!(router, app, "async_greet", &["World"]);
-        assert_eq!(body_as_string!(response), "\"Hello async, World!\"");
+        // the mock app has no `test_webview` webview to send the chunks to
+        let response = router
+            .handle_request(app.handle(), "test_webview", request("__CHANNEL__:1"))
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "WebviewNotFound");
 
-        let response = call_json!(router, app, "async_add", &[7, 3]);
-        assert_eq!(body_as_string!(response), "10");
+        // chunks are sent in order, large ones are fetched by the frontend after the response
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sent_ = Arc::clone(&sent);
+        let app = tauri::test::mock_builder()
+            .channel_interceptor(move |_webview, _callback, index, body| {
+                if let tauri::ipc::InvokeResponseBody::Raw(chunk) = body {
+                    sent_.lock().unwrap().push((index, chunk.clone()));
+                }
+                true
+            })
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        tauri::WebviewWindowBuilder::new(&app, "test_webview", Default::default())
+            .build()
+            .unwrap();
 
-        let response = call_json!(router, app, "async_with_result", &[5]);
-        assert_eq!(body_as_string!(response), "{\"Ok\":10}");
+        let chunks = vec![vec![0u8; 2048], vec![1; 16], vec![2; 4096], vec![3; 1024]];
+        let router = Router::new().command("download", {
+            let chunks = chunks.clone();
+            move || {
+                let (tx, rx) = tokio::sync::mpsc::channel(4);
+                let chunks = chunks.clone();
+                tokio::spawn(async move {
+                    for chunk in chunks {
+                        tx.send(chunk).await.unwrap();
+                    }
+                });
+                StreamBody::from_receiver(rx)
+            }
+        });
 
-        let response = call_json!(router, app, "async_with_result", &[-5]);
+        let response = router
+            .handle_request(
+                app.handle(),
+                "test_webview",
+                Request::builder()
+                    .method("POST")
+                    .uri("router://localhost/download")
+                    .header("X-Router-Channel", "__CHANNEL__:1")
+                    .body(Vec::new())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["X-Router-Chunks"], "4");
+        assert!(response.body().is_empty());
         assert_eq!(
-            body_as_string!(response),
-            "{\"Err\":\"Negative value not allowed\"}"
+            *sent.lock().unwrap(),
+            chunks.into_iter().enumerate().collect::<Vec<_>>()
         );
+    }
 
-        let response = call_json!(router, app, "async_with_app", &["Async"]);
-        assert_eq!(body_as_string!(response), "\"Async with App, Async!\"");
+    #[tokio::test]
+    async fn progress() {
+        use tauri::http::{Request, StatusCode};
+
+        let app = tauri::test::mock_app();
+
+        let router = Router::new().command("import", |progress: Progress<u32>, files: u32| {
+            assert!(!progress.is_enabled());
+            for i in 1..=files {
+                progress.send(i * 100 / files).unwrap();
+            }
+            files
+        });
+
+        // without a channel, progress events are dropped
+        let response = call_json!(router, app, "import", &[4]);
+        assert_eq!(body_as_string!(response), "4");
+
+        let request = |channel: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("router://localhost/import")
+                .header("X-Router-Progress-Channel", channel)
+                .body(serde_json::to_vec(&[4]).unwrap())
+                .unwrap()
+        };
+
+        let response = router
+            .handle_request(app.handle(), "test_webview", request("not a channel"))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "InvalidArgs");
+
+        // the mock app has no `test_webview` webview to send the events to
+        let response = router
+            .handle_request(app.handle(), "test_webview", request("__CHANNEL__:1"))
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error: serde_json::Value = body_as_json!(response);
+        assert_eq!(error["type"], "WebviewNotFound");
     }
 }
@@ -0,0 +1,235 @@
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use futures_core::Stream;
+use tauri::http::{header::*, HeaderValue, Method};
+use tauri::ipc::{Channel, InvokeResponseBody, JavaScriptChannelId};
+use tauri::{Manager, Runtime};
+
+use crate::IntoResponse;
+
+/// Header of the frontend channel that receives the chunks of a [StreamBody].
+pub(crate) const STREAM_CHANNEL_HEADER: &str = "x-router-channel";
+
+/// Response header with the number of chunks sent through the frontend channel.
+pub(crate) const STREAM_CHUNKS_HEADER: &str = "x-router-chunks";
+
+type ChunkStream = Pin<Box<dyn Stream<Item = Vec<u8>> + Send>>;
+
+/// A response streaming its body in chunks, so that the frontend can render them before the command completes.
+///
+/// The `router` protocol can only respond with a complete body, so the chunks are sent through
+/// a Tauri channel when the frontend passes an `onChunk` callback, and the request completes
+/// with an empty body and the number of chunks sent once the stream ends. Large chunks reach the frontend
+/// after the response, so the frontend waits for all of them before resolving.
+/// Otherwise, the chunks are collected into the response body.
+/// Timeouts and cancellation apply until the stream ends.
+///
+/// ## Example
+/// ```rust,no_run
+/// use tauri_plugin_router::{Router, StreamBody};
+///
+/// fn export() -> StreamBody {
+///     let (tx, rx) = tokio::sync::mpsc::channel(16);
+///     tauri::async_runtime::spawn(async move {
+///         for i in 0..1000 {
+///             if tx.send(format!("row {i}\n")).await.is_err() {
+///                 break;
+///             }
+///         }
+///     });
+///     StreamBody::from_receiver(rx).content_type("text/plain")
+/// }
+///
+/// fn main() {
+///     let router: Router<_> = Router::new().command("export", export);
+///
+///     let app = tauri::Builder::default()
+///         .plugin(tauri_plugin_router::init(router));
+/// }
+/// ```
+pub struct StreamBody {
+    stream: ChunkStream,
+    content_type: String,
+}
+
+impl StreamBody {
+    /// Creates a streaming response from a [Stream] of chunks, sent as `application/octet-stream` by default.
+    pub fn new<S, B>(stream: S) -> Self
+    where
+        S: Stream<Item = B> + Send + 'static,
+        B: Into<Vec<u8>>,
+    {
+        Self {
+            stream: Box::pin(MapInto(Box::pin(stream))),
+            content_type: "application/octet-stream".to_string(),
+        }
+    }
+
+    /// Creates a streaming response from the chunks sent to a [tokio::sync::mpsc] channel,
+    /// the stream ends when all the senders are dropped.
+    pub fn from_receiver<B>(receiver: tokio::sync::mpsc::Receiver<B>) -> Self
+    where
+        B: Into<Vec<u8>> + Send + 'static,
+    {
+        Self::new(ReceiverStream(receiver))
+    }
+
+    /// Sets the `Content-Type` of the response.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = content_type.into();
+        self
+    }
+}
+
+impl IntoResponse for StreamBody {
+    fn into_response(self) -> tauri::http::Response<Vec<u8>> {
+        let content_type = HeaderValue::from_str(&self.content_type)
+            .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+        tauri::http::Response::builder()
+            .header(CONTENT_TYPE, content_type)
+            .extension(StreamExtension(Arc::new(Mutex::new(Some(self.stream)))))
+            .body(Vec::new())
+            .unwrap()
+    }
+}
+
+/// The stream of a [StreamBody], attached as a response extension and forwarded by the router.
+#[derive(Clone)]
+struct StreamExtension(Arc<Mutex<Option<ChunkStream>>>);
+
+/// Maps the items of a stream into chunks.
+struct MapInto<S>(S);
+
+impl<S, B> Stream for MapInto<S>
+where
+    S: Stream<Item = B> + Unpin,
+    B: Into<Vec<u8>>,
+{
+    type Item = Vec<u8>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0)
+            .poll_next(cx)
+            .map(|chunk| chunk.map(Into::into))
+    }
+}
+
+/// A [Stream] of the values received by a [tokio::sync::mpsc::Receiver].
+struct ReceiverStream<B>(tokio::sync::mpsc::Receiver<B>);
+
+impl<B> Stream for ReceiverStream<B> {
+    type Item = B;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.0.poll_recv(cx)
+    }
+}
+
+/// The request a [StreamBody] responds to, captured before it is passed to the handler.
+pub(crate) struct StreamTarget<R: Runtime> {
+    app_handle: tauri::AppHandle<R>,
+    webview_label: String,
+    method: Method,
+    channel: Option<HeaderValue>,
+}
+
+impl<R: Runtime> StreamTarget<R> {
+    pub(crate) fn new(
+        app_handle: &tauri::AppHandle<R>,
+        webview_label: &str,
+        request: &tauri::http::Request<Vec<u8>>,
+    ) -> Self {
+        Self {
+            app_handle: app_handle.clone(),
+            webview_label: webview_label.to_string(),
+            method: request.method().clone(),
+            channel: request.headers().get(STREAM_CHANNEL_HEADER).cloned(),
+        }
+    }
+
+    /// Forwards the stream of a [StreamBody] attached to the response, if any.
+    pub(crate) async fn resolve(
+        &self,
+        mut response: tauri::http::Response<Vec<u8>>,
+    ) -> tauri::http::Response<Vec<u8>> {
+        let Some(StreamExtension(stream)) = response.extensions_mut().remove() else {
+            return response;
+        };
+        let Some(mut stream) = stream.lock().unwrap().take() else {
+            return response;
+        };
+        if self.method == Method::HEAD {
+            return response;
+        }
+
+        let channel = frontend_channel(
+            &self.app_handle,
+            &self.webview_label,
+            STREAM_CHANNEL_HEADER,
+            self.channel.as_ref(),
+        );
+        let channel = match channel {
+            Ok(channel) => channel,
+            Err(error) => return crate::response::error(error),
+        };
+
+        let mut chunks = 0u64;
+        while let Some(chunk) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+            match &channel {
+                Some(channel) if !chunk.is_empty() => {
+                    if let Err(e) = channel.send(InvokeResponseBody::Raw(chunk)) {
+                        let error = crate::Error::Io(format!("failed to send chunk: {e}"));
+                        return crate::response::error(error);
+                    }
+                    chunks += 1;
+                }
+                Some(_) => {}
+                None => response.body_mut().extend(chunk),
+            }
+        }
+
+        // chunks of 1 KiB or more are fetched by the frontend after the response
+        if channel.is_some() {
+            response
+                .headers_mut()
+                .insert(STREAM_CHUNKS_HEADER, chunks.into());
+        }
+        response
+    }
+}
+
+/// Resolves a frontend channel passed in a request header, if any.
+pub(crate) fn frontend_channel<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    webview_label: &str,
+    header: &str,
+    value: Option<&HeaderValue>,
+) -> crate::Result<Option<Channel<InvokeResponseBody>>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let id = value
+        .to_str()
+        .ok()
+        .and_then(|id| JavaScriptChannelId::from_str(id).ok())
+        .ok_or_else(|| crate::Error::InvalidArgs(format!("invalid `{header}` header")))?;
+
+    #[cfg(feature = "unstable")]
+    let webview = app_handle.get_webview(webview_label);
+    #[cfg(not(feature = "unstable"))]
+    let webview = app_handle
+        .get_webview_window(webview_label)
+        .map(|window| window.as_ref().clone());
+
+    let webview =
+        webview.ok_or_else(|| crate::Error::WebviewNotFound(webview_label.to_string()))?;
+    Ok(Some(id.channel_on(webview)))
+}